@@ -0,0 +1,353 @@
+use std::{path::PathBuf, str::FromStr};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use clap::{Args, Parser, Subcommand};
+use escrow_client::{
+    Address, EscrowAccount, Instruction, ProgramFeatures, find_escrow_address,
+    get_proceeds_vault_address, get_vault_address,
+    program::{Escrow, MakeInstructionData},
+};
+use solana_hash::Hash;
+use solana_keypair::{Keypair, read_keypair_file};
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::{Cluster, Result, read_escrow_dump};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "escrow-cli",
+    version,
+    about = "Create, take, refund and claim pinocchio_escrow offers"
+)]
+pub struct Cli {
+    /// JSON-RPC URL used to fetch accounts / blockhashes and to submit transactions
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://127.0.0.1:8899"
+    )]
+    pub url: String,
+
+    /// Recent blockhash for the transaction (fetched from `--url` when omitted)
+    #[arg(long, global = true)]
+    pub blockhash: Option<Hash>,
+
+    /// Sign with the keypair files and submit to `--url` instead of printing
+    /// an unsigned base64 transaction
+    #[arg(long, global = true)]
+    pub send: bool,
+
+    /// The deployed program was built with the `mint-policy` feature
+    #[arg(long, global = true)]
+    pub mint_policy: bool,
+
+    /// The deployed program was built with the `cpi-events` feature
+    #[arg(long, global = true)]
+    pub cpi_events: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Deposit token A and create an offer for token B
+    Make(MakeArgs),
+    /// Fill an open offer
+    Take(TakeArgs),
+    /// Cancel an open offer and return token A to the maker
+    Refund(RefundArgs),
+    /// Withdraw the proceeds of a filled claim-mode offer and close it
+    Claim(ClaimArgs),
+    /// Decode escrow state from an account dump file
+    Show(ShowArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct MakeArgs {
+    /// Maker keypair file (or a pubkey when only printing an unsigned transaction)
+    #[arg(long)]
+    pub maker: String,
+    #[arg(long)]
+    pub mint_a: Address,
+    #[arg(long)]
+    pub mint_b: Address,
+    #[arg(long)]
+    pub seed: u64,
+    /// Amount of token B wanted
+    #[arg(long)]
+    pub receive: u64,
+    /// Amount of token A deposited
+    #[arg(long)]
+    pub amount: u64,
+    /// Share of token B paid to a referrer, in basis points
+    #[arg(long, default_value_t = 0)]
+    pub referral_bps: u16,
+    /// Receives the escrow and vault rent on close (defaults to the maker)
+    #[arg(long)]
+    pub rent_recipient: Option<Address>,
+    /// Owner of the token B account paid on take (defaults to the maker)
+    #[arg(long)]
+    pub proceeds_recipient: Option<Address>,
+    /// Hold token B in a proceeds vault until the maker claims it
+    #[arg(long)]
+    pub claim: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct TakeArgs {
+    /// Taker keypair file (or a pubkey when only printing an unsigned transaction)
+    #[arg(long)]
+    pub taker: String,
+    #[arg(long)]
+    pub escrow: Address,
+    /// Read escrow state from a dump file instead of `--url`
+    #[arg(long)]
+    pub escrow_dump: Option<PathBuf>,
+    /// Wallet whose token B account receives the referral fee
+    #[arg(long)]
+    pub referrer: Option<Address>,
+    /// Least token A to receive; defaults to the vault balance fetched from `--url`
+    #[arg(long)]
+    pub min_amount_out: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct RefundArgs {
+    /// Maker keypair file (or a pubkey when only printing an unsigned transaction)
+    #[arg(long)]
+    pub maker: String,
+    #[arg(long)]
+    pub escrow: Address,
+    /// Read escrow state from a dump file instead of `--url`
+    #[arg(long)]
+    pub escrow_dump: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ClaimArgs {
+    /// Maker keypair file (or a pubkey when only printing an unsigned transaction)
+    #[arg(long)]
+    pub maker: String,
+    #[arg(long)]
+    pub escrow: Address,
+    /// Read escrow state from a dump file instead of `--url`
+    #[arg(long)]
+    pub escrow_dump: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Output of `solana account <ESCROW> --output json` or `--output-file`
+    pub dump: PathBuf,
+}
+
+/// 交易签名者：keypair 文件，或仅用于未签名交易的公钥
+pub enum SignerSource {
+    Keypair(Keypair),
+    Pubkey(Address),
+}
+
+impl SignerSource {
+    pub fn load(source: &str) -> Result<Self> {
+        match Address::from_str(source) {
+            Ok(pubkey) => Ok(Self::Pubkey(pubkey)),
+            Err(_) => Ok(Self::Keypair(
+                read_keypair_file(source).map_err(|e| format!("{source}: {e}"))?,
+            )),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Keypair(keypair) => keypair.pubkey(),
+            Self::Pubkey(pubkey) => *pubkey,
+        }
+    }
+}
+
+/// 执行一条命令，返回需打印的内容
+pub fn run(cli: &Cli, cluster: &dyn Cluster) -> Result<String> {
+    let features = ProgramFeatures {
+        mint_policy: cli.mint_policy,
+        cpi_events: cli.cpi_events,
+    };
+
+    match &cli.command {
+        Command::Make(args) => {
+            let maker = SignerSource::load(&args.maker)?;
+            let instruction = escrow_client::make(
+                &maker.address(),
+                &args.mint_a,
+                &args.mint_b,
+                &MakeInstructionData {
+                    seed: args.seed,
+                    receive: args.receive,
+                    amount: args.amount,
+                    referral_bps: args.referral_bps,
+                    rent_recipient: args.rent_recipient,
+                    proceeds_recipient: args.proceeds_recipient,
+                    settlement: if args.claim {
+                        Escrow::SETTLE_CLAIM
+                    } else {
+                        Escrow::SETTLE_PUSH
+                    },
+                },
+                features,
+            );
+            finish(cli, cluster, instruction, &maker)
+        }
+        Command::Take(args) => {
+            let taker = SignerSource::load(&args.taker)?;
+            let escrow = load_escrow(cluster, &args.escrow, args.escrow_dump.as_ref())?;
+            let min_amount_out = match args.min_amount_out {
+                Some(amount) => amount,
+                None => vault_amount(cluster, &args.escrow, &escrow)?,
+            };
+            let instruction = escrow_client::take(
+                &taker.address(),
+                &args.escrow,
+                &escrow,
+                args.referrer.as_ref(),
+                min_amount_out,
+                features,
+            );
+            finish(cli, cluster, instruction, &taker)
+        }
+        Command::Refund(args) => {
+            let maker = SignerSource::load(&args.maker)?;
+            let escrow = load_escrow(cluster, &args.escrow, args.escrow_dump.as_ref())?;
+            if escrow.maker != maker.address() {
+                return Err(format!("escrow {} belongs to {}", args.escrow, escrow.maker).into());
+            }
+            let instruction = escrow_client::refund(&args.escrow, &escrow, features);
+            finish(cli, cluster, instruction, &maker)
+        }
+        Command::Claim(args) => {
+            let maker = SignerSource::load(&args.maker)?;
+            let escrow = load_escrow(cluster, &args.escrow, args.escrow_dump.as_ref())?;
+            if escrow.maker != maker.address() {
+                return Err(format!("escrow {} belongs to {}", args.escrow, escrow.maker).into());
+            }
+            if !escrow.is_claim_mode() {
+                return Err(format!("escrow {} pays the maker on take", args.escrow).into());
+            }
+            if !escrow.is_filled() {
+                return Err(format!("escrow {} has not been taken yet", args.escrow).into());
+            }
+            let instruction = escrow_client::claim(&args.escrow, &escrow, features);
+            finish(cli, cluster, instruction, &maker)
+        }
+        Command::Show(args) => {
+            let escrow = read_escrow_dump(&args.dump)?;
+            let mut output = describe(&escrow);
+            if escrow.is_claim_mode() {
+                output.push('\n');
+                output.push_str(&describe_proceeds(cluster, &escrow));
+            }
+            Ok(output)
+        }
+    }
+}
+
+fn load_escrow(
+    cluster: &dyn Cluster,
+    address: &Address,
+    dump: Option<&PathBuf>,
+) -> Result<EscrowAccount> {
+    if let Some(dump) = dump {
+        return read_escrow_dump(dump);
+    }
+    let (owner, data) = cluster
+        .account(address)?
+        .ok_or_else(|| format!("escrow {address} not found"))?;
+    Ok(EscrowAccount::from_account(&owner, &data)?)
+}
+
+/// 金库当前的 Token A 余额，Take 以此作为至少收到的数量
+fn vault_amount(cluster: &dyn Cluster, address: &Address, escrow: &EscrowAccount) -> Result<u64> {
+    let vault = get_vault_address(address, &escrow.mint_a);
+    let (_, data) = cluster
+        .account(&vault)?
+        .ok_or_else(|| format!("vault {vault} not found; pass --min-amount-out to take offline"))?;
+    // SPL Token 账户的 amount 位于 64..72
+    let amount = data
+        .get(64..72)
+        .and_then(|amount| amount.try_into().ok())
+        .ok_or_else(|| format!("vault {vault} is not a token account"))?;
+    Ok(u64::from_le_bytes(amount))
+}
+
+/// 构造交易：`--send` 时签名并提交，否则输出未签名交易的 base64
+fn finish(
+    cli: &Cli,
+    cluster: &dyn Cluster,
+    instruction: Instruction,
+    signer: &SignerSource,
+) -> Result<String> {
+    let blockhash = match cli.blockhash {
+        Some(blockhash) => blockhash,
+        None => cluster.latest_blockhash()?,
+    };
+    let message = Message::new_with_blockhash(&[instruction], Some(&signer.address()), &blockhash);
+    let mut transaction = Transaction::new_unsigned(message);
+
+    if !cli.send {
+        return Ok(STANDARD.encode(bincode::serialize(&transaction)?));
+    }
+
+    let SignerSource::Keypair(keypair) = signer else {
+        return Err("--send requires a keypair file, not a pubkey".into());
+    };
+    transaction.try_sign(&[keypair], blockhash)?;
+    Ok(cluster.send_transaction(&transaction)?.to_string())
+}
+
+/// 收益金库及其余额；快照离线解码，查询失败时只标注而不报错
+fn describe_proceeds(cluster: &dyn Cluster, escrow: &EscrowAccount) -> String {
+    let (address, _) = find_escrow_address(&escrow.maker, escrow.seed);
+    let vault = get_proceeds_vault_address(&address, &escrow.mint_b);
+    let balance = match cluster.account(&vault) {
+        // SPL Token 账户的 amount 位于 64..72
+        Ok(Some((_, data))) => match data.get(64..72).and_then(|a| a.try_into().ok()) {
+            Some(amount) => u64::from_le_bytes(amount).to_string(),
+            None => "not a token account".to_string(),
+        },
+        Ok(None) => "closed".to_string(),
+        Err(err) => format!("unavailable ({err})"),
+    };
+
+    [
+        format!("proceeds_vault:     {vault}"),
+        format!("proceeds_balance:   {balance}"),
+    ]
+    .join("\n")
+}
+
+fn describe(escrow: &EscrowAccount) -> String {
+    let settlement = if escrow.is_claim_mode() {
+        "claim"
+    } else {
+        "push"
+    };
+    let status = if escrow.is_filled() { "filled" } else { "open" };
+    let (address, _) = find_escrow_address(&escrow.maker, escrow.seed);
+
+    [
+        format!("escrow:             {address}"),
+        format!("seed:               {}", escrow.seed),
+        format!("maker:              {}", escrow.maker),
+        format!("mint_a:             {}", escrow.mint_a),
+        format!("mint_b:             {}", escrow.mint_b),
+        format!("receive:            {}", escrow.receive),
+        format!("referral_bps:       {}", escrow.referral_bps),
+        format!("rent_recipient:     {}", escrow.rent_recipient),
+        format!("proceeds_recipient: {}", escrow.proceeds_recipient),
+        format!("settlement:         {settlement}"),
+        format!("status:             {status}"),
+    ]
+    .join("\n")
+}
@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use escrow_client::Address;
+use serde_json::{Value, json};
+use solana_hash::Hash;
+use solana_signature::Signature;
+use solana_transaction::Transaction;
+
+use crate::{Result, parse_json_account};
+
+/// 命令执行所需的全部链上交互
+pub trait Cluster {
+    /// 最新区块哈希 (未通过 `--blockhash` 指定时使用)
+    fn latest_blockhash(&self) -> Result<Hash>;
+
+    /// 账户的 `(owner, data)`，账户不存在时返回 `None`
+    fn account(&self, address: &Address) -> Result<Option<(Address, Vec<u8>)>>;
+
+    /// 提交已签名的交易，返回交易签名
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature>;
+}
+
+/// 通过 JSON-RPC 访问集群
+pub struct RpcCluster {
+    url: String,
+}
+
+impl RpcCluster {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))?
+            .into_json()?;
+
+        if let Some(error) = response.get("error") {
+            return Err(format!("{method} failed: {error}").into());
+        }
+        Ok(response["result"].clone())
+    }
+}
+
+impl Cluster for RpcCluster {
+    fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or("getLatestBlockhash returned no blockhash")?;
+        Ok(Hash::from_str(blockhash)?)
+    }
+
+    fn account(&self, address: &Address) -> Result<Option<(Address, Vec<u8>)>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => parse_json_account(value).map(Some),
+        }
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        let encoded = STANDARD.encode(bincode::serialize(transaction)?);
+        let result = self.call(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = result
+            .as_str()
+            .ok_or("sendTransaction returned no signature")?;
+        Ok(Signature::from_str(signature)?)
+    }
+}
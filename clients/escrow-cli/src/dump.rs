@@ -0,0 +1,42 @@
+use std::{fs, path::Path, str::FromStr};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use escrow_client::{Address, EscrowAccount};
+
+use crate::Result;
+
+/// 从账户快照文件解码 `Escrow`。
+///
+/// 支持两种格式：
+/// - `solana account <ADDRESS> --output json` 的 JSON 输出 (会校验账户所有者)；
+/// - `solana account <ADDRESS> --output-file <FILE>` 写出的原始账户数据。
+pub fn read_escrow_dump(path: &Path) -> Result<EscrowAccount> {
+    let bytes = fs::read(path)?;
+
+    match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(json) => {
+            let (owner, data) = parse_json_account(&json)?;
+            Ok(EscrowAccount::from_account(&owner, &data)?)
+        }
+        Err(_) => Ok(EscrowAccount::from_bytes(&bytes)?),
+    }
+}
+
+/// 解析 JSON 格式的账户 (兼容 RPC `getAccountInfo` 的 `value` 与 CLI 的 `account` 字段)
+pub fn parse_json_account(json: &serde_json::Value) -> Result<(Address, Vec<u8>)> {
+    let account = json.get("account").unwrap_or(json);
+
+    let owner = account["owner"]
+        .as_str()
+        .ok_or("account dump is missing `owner`")?;
+    let data = match &account["data"] {
+        serde_json::Value::Array(parts)
+            if parts.get(1).and_then(|e| e.as_str()) == Some("base64") =>
+        {
+            parts[0].as_str().unwrap_or_default()
+        }
+        _ => return Err("account dump data must be base64 encoded".into()),
+    };
+
+    Ok((Address::from_str(owner)?, STANDARD.decode(data)?))
+}
@@ -0,0 +1,18 @@
+/*
+escrow-cli 的实现：命令解析 (`cli`)、集群访问 (`cluster`) 与账户快照解析 (`dump`)。
+
+所有命令只通过 `Cluster` 读取账户、获取区块哈希和提交交易；二进制入口使用
+JSON-RPC 实现，测试可换成进程内的运行时，全程无需网络。
+ */
+
+pub mod cli;
+pub use cli::*;
+
+pub mod cluster;
+pub use cluster::*;
+
+pub mod dump;
+pub use dump::*;
+
+/// 命令行错误统一以字符串形式向上报告
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
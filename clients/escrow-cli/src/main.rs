@@ -0,0 +1,15 @@
+use clap::Parser;
+use escrow_cli::{Cli, RpcCluster};
+
+fn main() {
+    let cli = Cli::parse();
+    let cluster = RpcCluster::new(&cli.url);
+
+    match escrow_cli::run(&cli, &cluster) {
+        Ok(output) => println!("{output}"),
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    }
+}
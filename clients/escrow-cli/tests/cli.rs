@@ -0,0 +1,610 @@
+//! escrow-cli 的端到端测试：进程内运行时代替 RPC，不访问网络。
+//!
+//! 需要执行程序的用例依赖 `cargo build-sbf` 生成的 `target/deploy/pinocchio_escrow.so`，
+//! 标记为 `#[ignore]` (`just test-sbf` 运行)；文件不存在时跳过
+//! (设置 `ESCROW_REQUIRE_SBF=1` 时改为失败，CI 使用)。
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use clap::Parser;
+use escrow_cli::{Cli, Cluster, Result, run};
+use escrow_client::{
+    AccountMeta, Address, EscrowAccount, Instruction, PROGRAM_ID, TOKEN_PROGRAM_ID,
+    find_config_address, find_escrow_address, get_associated_token_address,
+    get_proceeds_vault_address, get_vault_address,
+    program::{Config, Escrow, Make, TakeInstructionData},
+};
+use mollusk_svm::{Mollusk, MolluskContext, program::loader_keys};
+use mollusk_svm_programs_token::{associated_token, token};
+use serde_json::json;
+use solana_account::Account;
+use solana_hash::Hash;
+use solana_keypair::{Keypair, write_keypair_file};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const BLOCKHASH: &str = "11111111111111111111111111111111";
+const LAMPORTS: u64 = 10_000_000_000;
+
+/// 内存账户表 + 可选的 Mollusk 运行时
+struct LocalCluster {
+    accounts: RefCell<HashMap<Address, Account>>,
+    runtime: Option<MolluskContext<HashMap<Address, Account>>>,
+}
+
+impl LocalCluster {
+    fn offline() -> Self {
+        Self {
+            accounts: RefCell::new(HashMap::new()),
+            runtime: None,
+        }
+    }
+
+    fn with_runtime() -> Option<Self> {
+        let elf =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/pinocchio_escrow.so");
+        let Ok(elf) = fs::read(&elf) else {
+            assert!(
+                std::env::var("ESCROW_REQUIRE_SBF").is_err(),
+                "{} is missing; run `cargo build-sbf` first",
+                elf.display()
+            );
+            eprintln!(
+                "skipping: {} not built (run `cargo build-sbf`)",
+                elf.display()
+            );
+            return None;
+        };
+
+        let mut mollusk = Mollusk::default();
+        mollusk.add_program_with_elf_and_loader(&PROGRAM_ID, &elf, &loader_keys::LOADER_V3);
+        token::add_program(&mut mollusk);
+        associated_token::add_program(&mut mollusk);
+
+        Some(Self {
+            accounts: RefCell::new(HashMap::new()),
+            runtime: Some(mollusk.with_context(HashMap::new())),
+        })
+    }
+
+    fn set_account(&self, address: Address, account: Account) {
+        match &self.runtime {
+            Some(runtime) => {
+                runtime.account_store.borrow_mut().insert(address, account);
+            }
+            None => {
+                self.accounts.borrow_mut().insert(address, account);
+            }
+        }
+    }
+
+    fn get_account(&self, address: &Address) -> Option<Account> {
+        match &self.runtime {
+            Some(runtime) => runtime.account_store.borrow().get(address).cloned(),
+            None => self.accounts.borrow().get(address).cloned(),
+        }
+    }
+}
+
+impl Cluster for LocalCluster {
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(Hash::from_str(BLOCKHASH)?)
+    }
+
+    fn account(&self, address: &Address) -> Result<Option<(Address, Vec<u8>)>> {
+        Ok(self
+            .get_account(address)
+            .filter(|account| account.lamports > 0)
+            .map(|account| (account.owner, account.data)))
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        let runtime = self.runtime.as_ref().ok_or("no runtime loaded")?;
+        transaction.verify()?;
+
+        let message = &transaction.message;
+        let instructions: Vec<Instruction> = message
+            .instructions
+            .iter()
+            .map(|compiled| Instruction {
+                program_id: message.account_keys[compiled.program_id_index as usize],
+                accounts: compiled
+                    .accounts
+                    .iter()
+                    .map(|&index| AccountMeta {
+                        pubkey: message.account_keys[index as usize],
+                        is_signer: message.is_signer(index as usize),
+                        is_writable: message.is_maybe_writable(index as usize, None),
+                    })
+                    .collect(),
+                data: compiled.data.clone(),
+            })
+            .collect();
+
+        let result = runtime.process_instruction_chain(&instructions);
+        if result.program_result.is_err() {
+            return Err(format!("transaction failed: {:?}", result.program_result).into());
+        }
+        Ok(transaction.signatures[0])
+    }
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("escrow-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+fn keypair_file(name: &str) -> (Keypair, String) {
+    let keypair = Keypair::new();
+    let path = temp_path(&format!("{name}.json"));
+    write_keypair_file(&keypair, &path).unwrap();
+    (keypair, path.to_string_lossy().into_owned())
+}
+
+fn cli(args: &[&str]) -> Cli {
+    Cli::parse_from(["escrow-cli"].iter().chain(args))
+}
+
+fn system_account() -> Account {
+    Account::new(LAMPORTS, 0, &Address::default())
+}
+
+fn mint_account() -> Account {
+    let mut data = vec![0u8; 82];
+    data[44] = 6; // decimals
+    data[45] = 1; // is_initialized
+    Account {
+        lamports: LAMPORTS,
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn token_account(mint: &Address, owner: &Address, amount: u64) -> Account {
+    let mut data = vec![0u8; 165];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1; // AccountState::Initialized
+    Account {
+        lamports: LAMPORTS,
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn token_amount(cluster: &LocalCluster, address: &Address) -> u64 {
+    let data = cluster.get_account(address).unwrap().data;
+    u64::from_le_bytes(data[64..72].try_into().unwrap())
+}
+
+/// 预置一个未暂停的 Config 账户
+fn config_account() -> Account {
+    let (_, bump) = find_config_address();
+    let mut data = vec![0u8; Config::LEN];
+    data[Config::LEN - 1] = bump;
+    Account {
+        lamports: LAMPORTS,
+        data,
+        owner: PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn dump_json(cluster: &LocalCluster, address: &Address, name: &str) -> PathBuf {
+    let account = cluster.get_account(address).unwrap();
+    let path = temp_path(name);
+    let dump = json!({
+        "pubkey": address.to_string(),
+        "account": {
+            "lamports": account.lamports,
+            "data": [STANDARD.encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+        },
+    });
+    fs::write(&path, dump.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn make_prints_unsigned_transaction_offline() {
+    let maker = Address::new_unique();
+    let mint_a = Address::new_unique();
+    let mint_b = Address::new_unique();
+
+    let output = run(
+        &cli(&[
+            "make",
+            "--maker",
+            &maker.to_string(),
+            "--mint-a",
+            &mint_a.to_string(),
+            "--mint-b",
+            &mint_b.to_string(),
+            "--seed",
+            "7",
+            "--receive",
+            "1000",
+            "--amount",
+            "500",
+            "--blockhash",
+            BLOCKHASH,
+        ]),
+        &LocalCluster::offline(),
+    )
+    .unwrap();
+
+    let transaction: Transaction = bincode::deserialize(&STANDARD.decode(output).unwrap()).unwrap();
+    assert_eq!(transaction.signatures, vec![Signature::default()]);
+    assert_eq!(transaction.message.account_keys[0], maker);
+
+    let instruction = &transaction.message.instructions[0];
+    assert_eq!(instruction.data[0], *Make::DISCRIMINATOR);
+    assert_eq!(instruction.accounts.len(), Make::ACCOUNTS.len());
+    let escrow = find_escrow_address(&maker, 7).0;
+    assert!(transaction.message.account_keys.contains(&escrow));
+}
+
+#[test]
+fn take_encodes_min_amount_out() {
+    let maker = Address::new_unique();
+    let taker = Address::new_unique();
+    let (address, bump) = find_escrow_address(&maker, 2);
+    let escrow = EscrowAccount {
+        seed: 2,
+        maker,
+        mint_a: Address::new_unique(),
+        mint_b: Address::new_unique(),
+        receive: 1_000,
+        bump,
+        referral_bps: 0,
+        rent_recipient: maker,
+        proceeds_recipient: maker,
+        settlement: Escrow::SETTLE_PUSH,
+        status: Escrow::STATUS_OPEN,
+    };
+    let dump = temp_path("take-escrow.bin");
+    fs::write(&dump, escrow.to_bytes()).unwrap();
+    let take = |extra: &[&str]| {
+        let (taker, address) = (taker.to_string(), address.to_string());
+        let mut args = vec![
+            "take",
+            "--taker",
+            &taker,
+            "--escrow",
+            &address,
+            "--escrow-dump",
+            dump.to_str().unwrap(),
+            "--blockhash",
+            BLOCKHASH,
+        ];
+        args.extend_from_slice(extra);
+        run(&cli(&args), &LocalCluster::offline())
+    };
+
+    // 离线时读不到金库余额，必须显式给出
+    let error = take(&[]).unwrap_err();
+    assert!(error.to_string().contains("--min-amount-out"), "{error}");
+
+    let output = take(&["--min-amount-out", "500"]).unwrap();
+    let transaction: Transaction = bincode::deserialize(&STANDARD.decode(output).unwrap()).unwrap();
+    let data = &transaction.message.instructions[0].data;
+    let args = TakeInstructionData::try_from(&data[1..]).unwrap();
+    assert_eq!((args.max_amount_in, args.min_amount_out), (1_000, 500));
+}
+
+#[test]
+fn send_requires_a_keypair_file() {
+    let maker = Address::new_unique();
+    let escrow = EscrowAccount {
+        seed: 1,
+        maker,
+        mint_a: Address::new_unique(),
+        mint_b: Address::new_unique(),
+        receive: 1_000,
+        bump: 255,
+        referral_bps: 0,
+        rent_recipient: maker,
+        proceeds_recipient: maker,
+        settlement: Escrow::SETTLE_PUSH,
+        status: Escrow::STATUS_OPEN,
+    };
+    let dump = temp_path("refund-escrow.bin");
+    fs::write(&dump, escrow.to_bytes()).unwrap();
+
+    let error = run(
+        &cli(&[
+            "refund",
+            "--maker",
+            &maker.to_string(),
+            "--escrow",
+            &find_escrow_address(&maker, 1).0.to_string(),
+            "--escrow-dump",
+            dump.to_str().unwrap(),
+            "--blockhash",
+            BLOCKHASH,
+            "--send",
+        ]),
+        &LocalCluster::offline(),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("requires a keypair"), "{error}");
+}
+
+#[test]
+fn show_decodes_raw_and_json_dumps() {
+    let maker = Address::new_unique();
+    let escrow = EscrowAccount {
+        seed: 3,
+        maker,
+        mint_a: Address::new_unique(),
+        mint_b: Address::new_unique(),
+        receive: 1_000,
+        bump: 255,
+        referral_bps: 25,
+        rent_recipient: maker,
+        proceeds_recipient: maker,
+        settlement: Escrow::SETTLE_CLAIM,
+        status: Escrow::STATUS_FILLED,
+    };
+
+    let raw = temp_path("escrow.bin");
+    fs::write(&raw, escrow.to_bytes()).unwrap();
+    let output = run(
+        &cli(&["show", raw.to_str().unwrap()]),
+        &LocalCluster::offline(),
+    )
+    .unwrap();
+    assert!(output.contains(&format!("maker:              {maker}")));
+    assert!(output.contains("settlement:         claim"));
+    assert!(output.contains("status:             filled"));
+    assert!(output.contains("proceeds_balance:   closed"));
+
+    let cluster = LocalCluster::offline();
+    let address = find_escrow_address(&maker, 3).0;
+    cluster.set_account(
+        address,
+        Account {
+            lamports: 1,
+            data: escrow.to_bytes().to_vec(),
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let proceeds_vault = get_proceeds_vault_address(&address, &escrow.mint_b);
+    cluster.set_account(
+        proceeds_vault,
+        token_account(&escrow.mint_b, &address, 1_000),
+    );
+    let json = dump_json(&cluster, &address, "escrow.json");
+    let output = run(&cli(&["show", json.to_str().unwrap()]), &cluster).unwrap();
+    assert!(output.contains(&format!("escrow:             {address}")));
+    assert!(output.contains("referral_bps:       25"));
+    assert!(output.contains(&format!("proceeds_vault:     {proceeds_vault}")));
+    assert!(output.contains("proceeds_balance:   1000"));
+}
+
+/// 准备 maker / taker 钱包、两种 mint 及其初始代币账户
+fn setup(cluster: &LocalCluster) -> (Keypair, String, Keypair, String, Address, Address) {
+    let (maker, maker_file) = keypair_file("maker");
+    let (taker, taker_file) = keypair_file("taker");
+    let mint_a = Address::new_unique();
+    let mint_b = Address::new_unique();
+
+    cluster.set_account(maker.pubkey(), system_account());
+    cluster.set_account(taker.pubkey(), system_account());
+    cluster.set_account(mint_a, mint_account());
+    cluster.set_account(mint_b, mint_account());
+    cluster.set_account(
+        get_associated_token_address(&maker.pubkey(), &mint_a, &TOKEN_PROGRAM_ID),
+        token_account(&mint_a, &maker.pubkey(), 500),
+    );
+    cluster.set_account(
+        get_associated_token_address(&taker.pubkey(), &mint_b, &TOKEN_PROGRAM_ID),
+        token_account(&mint_b, &taker.pubkey(), 1_000),
+    );
+    cluster.set_account(find_config_address().0, config_account());
+
+    (maker, maker_file, taker, taker_file, mint_a, mint_b)
+}
+
+fn make_offer(cluster: &LocalCluster, maker_file: &str, mint_a: &Address, mint_b: &Address) {
+    make_offer_with(cluster, maker_file, mint_a, mint_b, &[]);
+}
+
+fn make_offer_with(
+    cluster: &LocalCluster,
+    maker_file: &str,
+    mint_a: &Address,
+    mint_b: &Address,
+    extra: &[&str],
+) {
+    let (mint_a, mint_b) = (mint_a.to_string(), mint_b.to_string());
+    let mut args = vec![
+        "make",
+        "--maker",
+        maker_file,
+        "--mint-a",
+        &mint_a,
+        "--mint-b",
+        &mint_b,
+        "--seed",
+        "1",
+        "--receive",
+        "1000",
+        "--amount",
+        "500",
+        "--send",
+    ];
+    args.extend(extra);
+    run(&cli(&args), cluster).unwrap();
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn make_show_take_in_process() {
+    let Some(cluster) = LocalCluster::with_runtime() else {
+        return;
+    };
+    let (maker, maker_file, taker, taker_file, mint_a, mint_b) = setup(&cluster);
+
+    make_offer(&cluster, &maker_file, &mint_a, &mint_b);
+    let escrow = find_escrow_address(&maker.pubkey(), 1).0;
+    assert_eq!(
+        token_amount(&cluster, &get_vault_address(&escrow, &mint_a)),
+        500
+    );
+
+    let dump = dump_json(&cluster, &escrow, "live-escrow.json");
+    let output = run(&cli(&["show", dump.to_str().unwrap()]), &cluster).unwrap();
+    assert!(output.contains("status:             open"));
+
+    run(
+        &cli(&[
+            "take",
+            "--taker",
+            &taker_file,
+            "--escrow",
+            &escrow.to_string(),
+            "--send",
+        ]),
+        &cluster,
+    )
+    .unwrap();
+
+    let taker_ata_a = get_associated_token_address(&taker.pubkey(), &mint_a, &TOKEN_PROGRAM_ID);
+    let maker_ata_b = get_associated_token_address(&maker.pubkey(), &mint_b, &TOKEN_PROGRAM_ID);
+    assert_eq!(token_amount(&cluster, &taker_ata_a), 500);
+    assert_eq!(token_amount(&cluster, &maker_ata_b), 1_000);
+    assert!(cluster.account(&escrow).unwrap().is_none());
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn make_refund_in_process() {
+    let Some(cluster) = LocalCluster::with_runtime() else {
+        return;
+    };
+    let (maker, maker_file, _, _, mint_a, mint_b) = setup(&cluster);
+
+    make_offer(&cluster, &maker_file, &mint_a, &mint_b);
+    let escrow = find_escrow_address(&maker.pubkey(), 1).0;
+
+    run(
+        &cli(&[
+            "refund",
+            "--maker",
+            &maker_file,
+            "--escrow",
+            &escrow.to_string(),
+            "--send",
+        ]),
+        &cluster,
+    )
+    .unwrap();
+
+    let maker_ata_a = get_associated_token_address(&maker.pubkey(), &mint_a, &TOKEN_PROGRAM_ID);
+    assert_eq!(token_amount(&cluster, &maker_ata_a), 500);
+    assert!(cluster.account(&escrow).unwrap().is_none());
+}
+
+#[test]
+fn claim_rejects_an_open_offer_offline() {
+    let maker = Address::new_unique();
+    let escrow = EscrowAccount {
+        seed: 1,
+        maker,
+        mint_a: Address::new_unique(),
+        mint_b: Address::new_unique(),
+        receive: 1_000,
+        bump: 255,
+        referral_bps: 0,
+        rent_recipient: maker,
+        proceeds_recipient: maker,
+        settlement: Escrow::SETTLE_CLAIM,
+        status: Escrow::STATUS_OPEN,
+    };
+    let dump = temp_path("claim-escrow.bin");
+    fs::write(&dump, escrow.to_bytes()).unwrap();
+
+    let error = run(
+        &cli(&[
+            "claim",
+            "--maker",
+            &maker.to_string(),
+            "--escrow",
+            &find_escrow_address(&maker, 1).0.to_string(),
+            "--escrow-dump",
+            dump.to_str().unwrap(),
+            "--blockhash",
+            BLOCKHASH,
+        ]),
+        &LocalCluster::offline(),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("not been taken"), "{error}");
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn make_take_claim_in_process() {
+    let Some(cluster) = LocalCluster::with_runtime() else {
+        return;
+    };
+    let (maker, maker_file, _, taker_file, mint_a, mint_b) = setup(&cluster);
+
+    make_offer_with(&cluster, &maker_file, &mint_a, &mint_b, &["--claim"]);
+    let escrow = find_escrow_address(&maker.pubkey(), 1).0;
+
+    run(
+        &cli(&[
+            "take",
+            "--taker",
+            &taker_file,
+            "--escrow",
+            &escrow.to_string(),
+            "--send",
+        ]),
+        &cluster,
+    )
+    .unwrap();
+
+    let dump = dump_json(&cluster, &escrow, "filled-escrow.json");
+    let output = run(&cli(&["show", dump.to_str().unwrap()]), &cluster).unwrap();
+    assert!(output.contains("status:             filled"));
+    assert!(output.contains("proceeds_balance:   1000"));
+
+    run(
+        &cli(&[
+            "claim",
+            "--maker",
+            &maker_file,
+            "--escrow",
+            &escrow.to_string(),
+            "--send",
+        ]),
+        &cluster,
+    )
+    .unwrap();
+
+    let maker_ata_b = get_associated_token_address(&maker.pubkey(), &mint_b, &TOKEN_PROGRAM_ID);
+    assert_eq!(token_amount(&cluster, &maker_ata_b), 1_000);
+    assert!(cluster.account(&escrow).unwrap().is_none());
+}
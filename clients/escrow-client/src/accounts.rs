@@ -0,0 +1,150 @@
+use core::mem::offset_of;
+
+use pinocchio_escrow::{Escrow, LegacyEscrow};
+use solana_address::Address;
+
+use crate::{ClientError, PROGRAM_ID};
+
+// --- `Escrow` 的字节偏移 (与程序的 #[repr(C)] 布局逐字段对齐) ---
+// `anchor` feature 下数据以 8 字节的账户判别符开头
+const SEED: usize = Escrow::DISCRIMINATOR.len();
+const MAKER: usize = SEED + 8;
+const MINT_A: usize = MAKER + 32;
+const MINT_B: usize = MINT_A + 32;
+const RECEIVE: usize = MINT_B + 32;
+const BUMP: usize = RECEIVE + 8;
+const REFERRAL_BPS: usize = BUMP + 1;
+const RENT_RECIPIENT: usize = REFERRAL_BPS + 2;
+const PROCEEDS_RECIPIENT: usize = RENT_RECIPIENT + 32;
+const SETTLEMENT: usize = PROCEEDS_RECIPIENT + 32;
+const STATUS: usize = SETTLEMENT + 1;
+
+// 程序调整字段顺序、类型或追加字段时，这里会在编译期失败
+const _: () = {
+    assert!(offset_of!(Escrow, seed) == SEED);
+    assert!(offset_of!(Escrow, maker) == MAKER);
+    assert!(offset_of!(Escrow, mint_a) == MINT_A);
+    assert!(offset_of!(Escrow, mint_b) == MINT_B);
+    assert!(offset_of!(Escrow, receive) == RECEIVE);
+    assert!(offset_of!(Escrow, bump) == BUMP);
+    assert!(offset_of!(Escrow, referral_bps) == REFERRAL_BPS);
+    assert!(offset_of!(Escrow, rent_recipient) == RENT_RECIPIENT);
+    assert!(offset_of!(Escrow, proceeds_recipient) == PROCEEDS_RECIPIENT);
+    assert!(offset_of!(Escrow, settlement) == SETTLEMENT);
+    assert!(offset_of!(Escrow, status) == STATUS);
+    assert!(STATUS + 1 == Escrow::LEN);
+    assert!(offset_of!(LegacyEscrow, maker) == MAKER - SEED);
+    assert!(offset_of!(LegacyEscrow, bump) == BUMP - SEED);
+    assert!(BUMP - SEED + 1 == LegacyEscrow::LEN);
+};
+
+/// 解码后的托管账户 (拥有所有权，可脱离原始账户数据使用)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowAccount {
+    pub seed: u64,
+    pub maker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub receive: u64,
+    pub bump: u8,
+    pub referral_bps: u16,
+    pub rent_recipient: Address,
+    pub proceeds_recipient: Address,
+    pub settlement: u8,
+    pub status: u8,
+}
+
+impl EscrowAccount {
+    pub const LEN: usize = Escrow::LEN;
+
+    /// 从账户数据解码 (不检查账户所有者)。
+    /// 旧版 (`LegacyEscrow`) 托管按程序 Refund 时的规则补齐：无推荐费、接收者均为创建者。
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ClientError> {
+        if data.len() == LegacyEscrow::LEN {
+            return Ok(Self::from_legacy_bytes(data));
+        }
+        if data.len() != Self::LEN {
+            return Err(ClientError::InvalidAccountLength {
+                expected: Self::LEN,
+                actual: data.len(),
+            });
+        }
+        if !data.starts_with(Escrow::DISCRIMINATOR) {
+            return Err(ClientError::InvalidDiscriminator);
+        }
+
+        Ok(Self {
+            seed: read_u64(data, SEED),
+            maker: read_address(data, MAKER),
+            mint_a: read_address(data, MINT_A),
+            mint_b: read_address(data, MINT_B),
+            receive: read_u64(data, RECEIVE),
+            bump: data[BUMP],
+            referral_bps: u16::from_le_bytes([data[REFERRAL_BPS], data[REFERRAL_BPS + 1]]),
+            rent_recipient: read_address(data, RENT_RECIPIENT),
+            proceeds_recipient: read_address(data, PROCEEDS_RECIPIENT),
+            settlement: data[SETTLEMENT],
+            status: data[STATUS],
+        })
+    }
+
+    fn from_legacy_bytes(data: &[u8]) -> Self {
+        // 旧版布局没有判别符，字段与当前布局的前缀相同
+        let maker = read_address(data, MAKER - SEED);
+        Self {
+            seed: read_u64(data, 0),
+            maker,
+            mint_a: read_address(data, MINT_A - SEED),
+            mint_b: read_address(data, MINT_B - SEED),
+            receive: read_u64(data, RECEIVE - SEED),
+            bump: data[BUMP - SEED],
+            referral_bps: 0,
+            rent_recipient: maker,
+            proceeds_recipient: maker,
+            settlement: Escrow::SETTLE_PUSH,
+            status: Escrow::STATUS_OPEN,
+        }
+    }
+
+    /// 从 RPC 返回的账户解码，并确认其属于托管程序
+    pub fn from_account(owner: &Address, data: &[u8]) -> Result<Self, ClientError> {
+        if owner != &PROGRAM_ID {
+            return Err(ClientError::InvalidOwner);
+        }
+        Self::from_bytes(data)
+    }
+
+    /// 编码回账户数据 (测试或模拟运行时预置账户时使用)
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[..SEED].copy_from_slice(Escrow::DISCRIMINATOR);
+        data[SEED..MAKER].copy_from_slice(&self.seed.to_le_bytes());
+        data[MAKER..MINT_A].copy_from_slice(self.maker.as_ref());
+        data[MINT_A..MINT_B].copy_from_slice(self.mint_a.as_ref());
+        data[MINT_B..RECEIVE].copy_from_slice(self.mint_b.as_ref());
+        data[RECEIVE..BUMP].copy_from_slice(&self.receive.to_le_bytes());
+        data[BUMP] = self.bump;
+        data[REFERRAL_BPS..RENT_RECIPIENT].copy_from_slice(&self.referral_bps.to_le_bytes());
+        data[RENT_RECIPIENT..PROCEEDS_RECIPIENT].copy_from_slice(self.rent_recipient.as_ref());
+        data[PROCEEDS_RECIPIENT..SETTLEMENT].copy_from_slice(self.proceeds_recipient.as_ref());
+        data[SETTLEMENT] = self.settlement;
+        data[STATUS] = self.status;
+        data
+    }
+
+    pub fn is_claim_mode(&self) -> bool {
+        self.settlement == Escrow::SETTLE_CLAIM
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.status == Escrow::STATUS_FILLED
+    }
+}
+
+fn read_address(data: &[u8], offset: usize) -> Address {
+    Address::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,64 @@
+use core::fmt;
+
+use pinocchio_escrow::{AccountFlagError, AccountSpec};
+
+/// 客户端解码错误
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientError {
+    /// 账户数据长度与 `Escrow::LEN` 不符
+    InvalidAccountLength { expected: usize, actual: usize },
+    /// 账户数据不以 Anchor 账户判别符开头 (仅 `anchor` feature)
+    InvalidDiscriminator,
+    /// 账户不属于托管程序
+    InvalidOwner,
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAccountLength { expected, actual } => {
+                write!(
+                    f,
+                    "invalid escrow account length: expected {expected}, got {actual}"
+                )
+            }
+            Self::InvalidDiscriminator => write!(f, "invalid escrow account discriminator"),
+            Self::InvalidOwner => write!(f, "account is not owned by the escrow program"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+/// 程序在任何 CPI 之前拒绝的账户标志错误，附带出错位置的账户名
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountFlagFailure {
+    pub error: AccountFlagError,
+    pub account: &'static str,
+}
+
+impl AccountFlagFailure {
+    /// 由 `ProgramError::Custom` 的错误码还原；`specs` 为出错指令的 `ACCOUNTS`
+    pub fn from_code(code: u32, specs: &[AccountSpec]) -> Option<Self> {
+        let error = AccountFlagError::from_code(code)?;
+        let spec = specs.get(error.slot())?;
+        Some(Self {
+            error,
+            account: spec.name,
+        })
+    }
+}
+
+impl fmt::Display for AccountFlagFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let slot = self.error.slot();
+        match self.error {
+            AccountFlagError::MissingSigner(_) => {
+                write!(f, "account #{slot} `{}` must sign", self.account)
+            }
+            AccountFlagError::NotWritable(_) => {
+                write!(f, "account #{slot} `{}` must be writable", self.account)
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use pinocchio_escrow::{
+    AccountSpec, Claim, Escrow, Make, MakeInstructionData, Refund, Take, TakeInstructionData,
+};
+use solana_address::Address;
+use solana_instruction::{AccountMeta, Instruction};
+
+use crate::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, EscrowAccount, PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    find_config_address, find_escrow_address, find_event_authority_address,
+    find_mint_policy_address, get_associated_token_address, get_proceeds_vault_address,
+    get_vault_address,
+};
+
+/// 目标部署启用的 feature，决定需要附带哪些尾部账户
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProgramFeatures {
+    /// `mint-policy`：Make 需附带 mint_a / mint_b 的白名单账户
+    pub mint_policy: bool,
+    /// `cpi-events`：每条指令需附带 `[event_authority, program]`
+    pub cpi_events: bool,
+}
+
+/// 创建报价；`args` 中未指定的收款人 / 租金接收者默认为 `maker`
+pub fn make(
+    maker: &Address,
+    mint_a: &Address,
+    mint_b: &Address,
+    args: &MakeInstructionData,
+    features: ProgramFeatures,
+) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, args.seed);
+
+    let mut accounts = fixed_accounts(
+        &Make::ACCOUNTS,
+        [
+            *maker,
+            escrow,
+            *mint_a,
+            *mint_b,
+            get_associated_token_address(maker, mint_a, &TOKEN_PROGRAM_ID),
+            get_vault_address(&escrow, mint_a),
+            SYSTEM_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            find_config_address().0,
+        ],
+    );
+    if features.mint_policy {
+        accounts.push(AccountMeta::new_readonly(
+            find_mint_policy_address(mint_a).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            find_mint_policy_address(mint_b).0,
+            false,
+        ));
+    }
+    push_event_accounts(&mut accounts, features);
+    if args.settlement == Escrow::SETTLE_CLAIM {
+        accounts.push(AccountMeta::new(
+            get_proceeds_vault_address(&escrow, mint_b),
+            false,
+        ));
+    }
+
+    let mut data = Vec::with_capacity(1 + MakeInstructionData::LEN);
+    data.push(*Make::DISCRIMINATOR);
+    data.extend_from_slice(&args.to_bytes(maker));
+
+    Instruction::new_with_bytes(PROGRAM_ID, &data, accounts)
+}
+
+/// 接受报价；`referrer` 为推荐人钱包，推荐费从 `receive` 中分出，打入其 mint_b ATA。
+///
+/// 最多支付 `escrow.receive`，且至少收到 `min_amount_out` 的 Token A
+/// (通常传读取报价时金库的余额)：报价在读取后被重建为更高的价格或更少的数量时成交失败
+pub fn take(
+    taker: &Address,
+    escrow_address: &Address,
+    escrow: &EscrowAccount,
+    referrer: Option<&Address>,
+    min_amount_out: u64,
+    features: ProgramFeatures,
+) -> Instruction {
+    let maker_ata_b = if escrow.is_claim_mode() {
+        get_proceeds_vault_address(escrow_address, &escrow.mint_b)
+    } else {
+        get_associated_token_address(
+            &escrow.proceeds_recipient,
+            &escrow.mint_b,
+            &TOKEN_PROGRAM_ID,
+        )
+    };
+
+    let mut accounts = fixed_accounts(
+        &Take::ACCOUNTS,
+        [
+            *taker,
+            escrow.maker,
+            *escrow_address,
+            escrow.mint_a,
+            escrow.mint_b,
+            get_vault_address(escrow_address, &escrow.mint_a),
+            get_associated_token_address(taker, &escrow.mint_a, &TOKEN_PROGRAM_ID),
+            get_associated_token_address(taker, &escrow.mint_b, &TOKEN_PROGRAM_ID),
+            maker_ata_b,
+            SYSTEM_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            escrow.rent_recipient,
+            escrow.proceeds_recipient,
+            find_config_address().0,
+        ],
+    );
+    push_event_accounts(&mut accounts, features);
+    if let Some(referrer) = referrer {
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(referrer, &escrow.mint_b, &TOKEN_PROGRAM_ID),
+            false,
+        ));
+    }
+
+    let args = TakeInstructionData {
+        max_amount_in: escrow.receive,
+        min_amount_out,
+    };
+    let mut data = Vec::with_capacity(1 + TakeInstructionData::LEN);
+    data.push(*Take::DISCRIMINATOR);
+    data.extend_from_slice(&args.to_bytes());
+
+    Instruction::new_with_bytes(PROGRAM_ID, &data, accounts)
+}
+
+/// 创建者取消报价
+pub fn refund(
+    escrow_address: &Address,
+    escrow: &EscrowAccount,
+    features: ProgramFeatures,
+) -> Instruction {
+    let mut accounts = fixed_accounts(
+        &Refund::ACCOUNTS,
+        [
+            escrow.maker,
+            *escrow_address,
+            escrow.mint_a,
+            get_vault_address(escrow_address, &escrow.mint_a),
+            get_associated_token_address(&escrow.maker, &escrow.mint_a, &TOKEN_PROGRAM_ID),
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID,
+            escrow.rent_recipient,
+        ],
+    );
+    push_event_accounts(&mut accounts, features);
+    if escrow.is_claim_mode() {
+        // 收益金库可能被转入过代币，转给收益接收者后才能关闭
+        accounts.extend([
+            AccountMeta::new(
+                get_proceeds_vault_address(escrow_address, &escrow.mint_b),
+                false,
+            ),
+            AccountMeta::new_readonly(escrow.mint_b, false),
+            AccountMeta::new_readonly(escrow.proceeds_recipient, false),
+            AccountMeta::new(
+                get_associated_token_address(
+                    &escrow.proceeds_recipient,
+                    &escrow.mint_b,
+                    &TOKEN_PROGRAM_ID,
+                ),
+                false,
+            ),
+        ]);
+    }
+
+    Instruction::new_with_bytes(PROGRAM_ID, &[*Refund::DISCRIMINATOR], accounts)
+}
+
+/// Claim 模式下创建者领取收益
+pub fn claim(
+    escrow_address: &Address,
+    escrow: &EscrowAccount,
+    features: ProgramFeatures,
+) -> Instruction {
+    let mut accounts = fixed_accounts(
+        &Claim::ACCOUNTS,
+        [
+            escrow.maker,
+            *escrow_address,
+            escrow.mint_b,
+            get_proceeds_vault_address(escrow_address, &escrow.mint_b),
+            get_associated_token_address(
+                &escrow.proceeds_recipient,
+                &escrow.mint_b,
+                &TOKEN_PROGRAM_ID,
+            ),
+            escrow.proceeds_recipient,
+            escrow.rent_recipient,
+            SYSTEM_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+        ],
+    );
+    push_event_accounts(&mut accounts, features);
+
+    Instruction::new_with_bytes(PROGRAM_ID, &[*Claim::DISCRIMINATOR], accounts)
+}
+
+/// 可选尾部账户的 "未提供" 占位 (本程序 ID)：跳过某个可选账户、继续传入其后的账户时使用
+pub fn none_account() -> AccountMeta {
+    AccountMeta::new_readonly(PROGRAM_ID, false)
+}
+
+/// 按程序声明的 `ACCOUNTS` 生成固定账户的元数据
+fn fixed_accounts<const N: usize>(
+    specs: &[AccountSpec; N],
+    keys: [Address; N],
+) -> Vec<AccountMeta> {
+    specs
+        .iter()
+        .zip(keys)
+        .map(|(spec, key)| AccountMeta {
+            pubkey: key,
+            is_signer: spec.signer,
+            is_writable: spec.writable,
+        })
+        .collect()
+}
+
+fn push_event_accounts(accounts: &mut Vec<AccountMeta>, features: ProgramFeatures) {
+    if features.cpi_events {
+        accounts.push(AccountMeta::new_readonly(
+            find_event_authority_address().0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(PROGRAM_ID, false));
+    }
+}
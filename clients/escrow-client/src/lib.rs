@@ -0,0 +1,27 @@
+/*
+pinocchio_escrow 的链下 Rust 客户端：
+
+- `pda`：托管 / 金库 / 配置等地址推导；
+- `instructions`：构造 Make / Take / Refund / Claim 的 `solana_instruction::Instruction`；
+- `accounts`：将 `Escrow` 账户数据解码为拥有所有权的结构体。
+
+账户顺序、标志与判别符全部取自程序 crate 本身 (`ACCOUNTS` / `DISCRIMINATOR`)，
+数据布局通过编译期断言与程序的常量对齐，布局变化会直接导致编译失败。
+ */
+
+pub mod accounts;
+pub use accounts::*;
+
+pub mod error;
+pub use error::*;
+
+pub mod instructions;
+pub use instructions::*;
+
+pub mod pda;
+pub use pda::*;
+
+pub use pinocchio_escrow as program;
+pub use pinocchio_escrow::ID as PROGRAM_ID;
+pub use solana_address::Address;
+pub use solana_instruction::{AccountMeta, Instruction};
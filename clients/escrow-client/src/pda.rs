@@ -0,0 +1,57 @@
+use pinocchio_escrow::{CONFIG_SEED, ESCROW_SEED, EVENT_AUTHORITY_SEED, MINT_POLICY_SEED};
+use solana_address::Address;
+
+use crate::PROGRAM_ID;
+
+// --- 外部程序地址 ---
+pub const SYSTEM_PROGRAM_ID: Address = Address::new_from_array([0; 32]);
+pub const TOKEN_PROGRAM_ID: Address =
+    solana_address::address!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Address =
+    solana_address::address!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// 托管 PDA：`[ESCROW_SEED, maker, seed.to_le_bytes()]`
+pub fn find_escrow_address(maker: &Address, seed: u64) -> (Address, u8) {
+    Address::find_program_address(
+        &[ESCROW_SEED, maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// 全局配置 PDA：`[CONFIG_SEED]`
+pub fn find_config_address() -> (Address, u8) {
+    Address::find_program_address(&[CONFIG_SEED], &PROGRAM_ID)
+}
+
+/// mint 白名单 PDA：`[MINT_POLICY_SEED, mint]`
+pub fn find_mint_policy_address(mint: &Address) -> (Address, u8) {
+    Address::find_program_address(&[MINT_POLICY_SEED, mint.as_ref()], &PROGRAM_ID)
+}
+
+/// 自调用事件的签名 PDA：`[EVENT_AUTHORITY_SEED]`
+pub fn find_event_authority_address() -> (Address, u8) {
+    Address::find_program_address(&[EVENT_AUTHORITY_SEED], &PROGRAM_ID)
+}
+
+/// 关联代币账户地址 (ATA)
+pub fn get_associated_token_address(
+    wallet: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> Address {
+    Address::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// 金库：托管 PDA 持有的 mint_a ATA
+pub fn get_vault_address(escrow: &Address, mint_a: &Address) -> Address {
+    get_associated_token_address(escrow, mint_a, &TOKEN_PROGRAM_ID)
+}
+
+/// 收益金库 (Claim 模式)：托管 PDA 持有的 mint_b ATA
+pub fn get_proceeds_vault_address(escrow: &Address, mint_b: &Address) -> Address {
+    get_associated_token_address(escrow, mint_b, &TOKEN_PROGRAM_ID)
+}
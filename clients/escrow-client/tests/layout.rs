@@ -0,0 +1,289 @@
+//! 客户端编码与程序自身解析逻辑的一致性检查
+
+use escrow_client::{
+    AccountFlagFailure, Address, EscrowAccount, ProgramFeatures, TOKEN_PROGRAM_ID,
+    find_config_address, find_escrow_address, find_event_authority_address,
+    get_associated_token_address, get_proceeds_vault_address, make, refund, take,
+};
+use pinocchio_escrow::{
+    AccountFlagError, CONFIG_ADDRESS, CONFIG_BUMP, EVENT_AUTHORITY_ADDRESS, EVENT_AUTHORITY_BUMP,
+    Escrow, LegacyEscrow, Make, MakeInstructionData, Refund, RefundInstructionData, Take,
+    TakeInstructionData, anchor,
+    idl::{self, IdlInstruction},
+};
+
+fn address(byte: u8) -> Address {
+    Address::new_from_array([byte; 32])
+}
+
+fn make_args(settlement: u8) -> MakeInstructionData {
+    MakeInstructionData {
+        seed: 42,
+        receive: 1_000,
+        amount: 500,
+        referral_bps: 250,
+        rent_recipient: Some(address(7)),
+        proceeds_recipient: None,
+        settlement,
+    }
+}
+
+#[test]
+fn make_data_round_trips_through_program_parser() {
+    let maker = address(1);
+    let ix = make(
+        &maker,
+        &address(2),
+        &address(3),
+        &make_args(Escrow::SETTLE_PUSH),
+        ProgramFeatures::default(),
+    );
+
+    assert_eq!(ix.program_id, escrow_client::PROGRAM_ID);
+    assert_eq!(ix.accounts.len(), Make::ACCOUNTS.len());
+    assert_eq!(ix.data[0], *Make::DISCRIMINATOR);
+    assert_eq!(ix.accounts[1].pubkey, find_escrow_address(&maker, 42).0);
+    // 程序只接受 SPL Token (`#[account(address = pinocchio_token::ID)]`)
+    let token_program = Make::ACCOUNTS
+        .iter()
+        .position(|spec| spec.name == "token_program")
+        .unwrap();
+    assert_eq!(ix.accounts[token_program].pubkey, TOKEN_PROGRAM_ID);
+
+    let parsed = MakeInstructionData::try_from(&ix.data[1..]).unwrap();
+    assert_eq!(parsed.seed, 42);
+    assert_eq!(parsed.receive, 1_000);
+    assert_eq!(parsed.amount, 500);
+    assert_eq!(parsed.referral_bps, 250);
+    assert_eq!(parsed.rent_recipient, Some(address(7)));
+    assert_eq!(parsed.proceeds_recipient, Some(maker));
+    assert_eq!(parsed.settlement, Escrow::SETTLE_PUSH);
+}
+
+#[test]
+fn escrow_account_decodes_program_layout() {
+    let mut data = [0u8; Escrow::LEN];
+    {
+        let escrow = Escrow::load_mut(&mut data).unwrap();
+        escrow.set_inner(9, address(1), address(2), address(3), 1_000, [254]);
+        escrow.set_referral_bps(300);
+        escrow.set_rent_recipient(address(4));
+        escrow.set_proceeds_recipient(address(5));
+        escrow.set_settlement(Escrow::SETTLE_CLAIM);
+        escrow.set_status(Escrow::STATUS_FILLED);
+    }
+
+    let decoded = EscrowAccount::from_bytes(&data).unwrap();
+    assert_eq!(decoded.seed, 9);
+    assert_eq!(decoded.maker, address(1));
+    assert_eq!(decoded.mint_a, address(2));
+    assert_eq!(decoded.mint_b, address(3));
+    assert_eq!(decoded.receive, 1_000);
+    assert_eq!(decoded.bump, 254);
+    assert_eq!(decoded.referral_bps, 300);
+    assert_eq!(decoded.rent_recipient, address(4));
+    assert_eq!(decoded.proceeds_recipient, address(5));
+    assert!(decoded.is_claim_mode());
+    assert!(decoded.is_filled());
+    assert_eq!(decoded.to_bytes(), data);
+
+    assert!(EscrowAccount::from_bytes(&data[1..]).is_err());
+}
+
+#[test]
+fn legacy_escrow_decodes_with_refund_defaults() {
+    let data = [
+        &9u64.to_le_bytes()[..],
+        address(1).as_ref(),
+        address(2).as_ref(),
+        address(3).as_ref(),
+        &1_000u64.to_le_bytes(),
+        &[254],
+    ]
+    .concat();
+    assert_eq!(data.len(), LegacyEscrow::LEN);
+
+    let decoded = EscrowAccount::from_bytes(&data).unwrap();
+    assert_eq!(decoded.seed, 9);
+    assert_eq!(decoded.maker, address(1));
+    assert_eq!(decoded.mint_b, address(3));
+    assert_eq!(decoded.receive, 1_000);
+    assert_eq!(decoded.bump, 254);
+    assert_eq!(decoded.referral_bps, 0);
+    assert_eq!(decoded.rent_recipient, address(1));
+    assert!(!decoded.is_claim_mode());
+    assert!(!decoded.is_filled());
+}
+
+#[test]
+fn trailing_accounts_follow_settlement_and_features() {
+    let maker = address(1);
+    let (escrow_address, bump) = find_escrow_address(&maker, 42);
+    let escrow = EscrowAccount {
+        seed: 42,
+        maker,
+        mint_a: address(2),
+        mint_b: address(3),
+        receive: 1_000,
+        bump,
+        referral_bps: 0,
+        rent_recipient: maker,
+        proceeds_recipient: maker,
+        settlement: Escrow::SETTLE_CLAIM,
+        status: Escrow::STATUS_OPEN,
+    };
+    let proceeds_vault = get_proceeds_vault_address(&escrow_address, &escrow.mint_b);
+    let features = ProgramFeatures {
+        mint_policy: false,
+        cpi_events: true,
+    };
+
+    // Claim 模式：maker_ata_b 槽位即收益金库
+    let ix = take(
+        &address(9),
+        &escrow_address,
+        &escrow,
+        Some(&address(8)),
+        500,
+        features,
+    );
+    assert_eq!(ix.accounts.len(), Take::ACCOUNTS.len() + 3);
+    assert_eq!(ix.accounts[8].pubkey, proceeds_vault);
+    assert!(ix.accounts[0].is_signer);
+
+    // 最多支付读取时的售价，至少收到调用方给出的数量
+    let args = TakeInstructionData::try_from(&ix.data[1..]).unwrap();
+    assert_eq!((args.max_amount_in, args.min_amount_out), (1_000, 500));
+
+    let ix = refund(&escrow_address, &escrow, features);
+    assert_eq!(ix.accounts.len(), Refund::ACCOUNTS.len() + 6);
+    assert_eq!(
+        ix.accounts[Refund::ACCOUNTS.len() + 2].pubkey,
+        proceeds_vault
+    );
+    assert_eq!(
+        ix.accounts.last().unwrap().pubkey,
+        get_associated_token_address(&maker, &escrow.mint_b, &TOKEN_PROGRAM_ID)
+    );
+    assert_eq!(ix.data, [*Refund::DISCRIMINATOR]);
+}
+
+#[test]
+fn instruction_data_versions_are_length_tagged() {
+    // 旧客户端只发判别符：Take 不做限制，Refund 没有参数
+    let args = TakeInstructionData::try_from(&[][..]).unwrap();
+    assert_eq!(args.max_amount_in, u64::MAX);
+    assert_eq!(args.min_amount_out, 0);
+    assert!(RefundInstructionData::try_from(&[][..]).is_ok());
+
+    // 字段组不完整或多出未知字节都会被拒绝
+    let bytes = TakeInstructionData::UNCHECKED.to_bytes();
+    assert!(TakeInstructionData::try_from(&bytes[..8]).is_err());
+    assert!(TakeInstructionData::try_from(&[&bytes[..], &[0]].concat()[..]).is_err());
+    assert!(RefundInstructionData::try_from(&[0][..]).is_err());
+
+    // Make 的每个版本都能解析，版本之间的长度不行
+    let full = make_args(Escrow::SETTLE_CLAIM).to_bytes(&address(1));
+    for len in 0..=full.len() + 1 {
+        let data = [&full[..], &[0]].concat();
+        let parsed = MakeInstructionData::try_from(&data[..len]);
+        assert_eq!(
+            parsed.is_ok(),
+            matches!(len, 24 | 26 | 90 | 91),
+            "len {len}"
+        );
+    }
+}
+
+#[test]
+fn pda_constants_are_canonical() {
+    assert_eq!(find_config_address(), (CONFIG_ADDRESS, CONFIG_BUMP));
+    assert_eq!(
+        find_event_authority_address(),
+        (EVENT_AUTHORITY_ADDRESS, EVENT_AUTHORITY_BUMP)
+    );
+    // 暂停检查按名字定位 Config，且它是最后一个固定账户
+    assert_eq!(Make::CONFIG_INDEX, Make::ACCOUNTS.len() - 1);
+    assert_eq!(Take::CONFIG_INDEX, Take::ACCOUNTS.len() - 1);
+}
+
+#[test]
+fn account_metas_follow_declared_flags() {
+    let maker = address(1);
+    let ix = make(
+        &maker,
+        &address(2),
+        &address(3),
+        &make_args(Escrow::SETTLE_PUSH),
+        ProgramFeatures::default(),
+    );
+    for (meta, spec) in ix.accounts.iter().zip(&Make::ACCOUNTS) {
+        assert_eq!(
+            (meta.is_signer, meta.is_writable),
+            (spec.signer, spec.writable)
+        );
+    }
+}
+
+#[test]
+fn account_flag_errors_name_the_slot() {
+    let vault = Take::ACCOUNTS
+        .iter()
+        .position(|spec| spec.name == "vault")
+        .unwrap();
+    let code = AccountFlagError::NotWritable(vault as u8).code();
+
+    let failure = AccountFlagFailure::from_code(code, &Take::ACCOUNTS).unwrap();
+    assert_eq!(failure.account, "vault");
+    assert_eq!(failure.to_string(), "account #5 `vault` must be writable");
+
+    let code = AccountFlagError::MissingSigner(0).code();
+    let failure = AccountFlagFailure::from_code(code, &Refund::ACCOUNTS).unwrap();
+    assert_eq!(failure.to_string(), "account #0 `maker` must sign");
+
+    // 托管程序自身的错误码不会被误认
+    assert_eq!(AccountFlagFailure::from_code(9, &Take::ACCOUNTS), None);
+}
+
+#[test]
+fn anchor_discriminators_match_sighash() {
+    // 与 Anchor 生成的值一致：sha256("global:make")[..8] 与 sha256("account:Escrow")[..8]
+    let make = idl::INSTRUCTIONS
+        .iter()
+        .find(|ix| ix.name == "make")
+        .unwrap();
+    assert_eq!(
+        make.anchor_discriminator(),
+        [138, 227, 232, 77, 223, 166, 96, 197]
+    );
+    assert_eq!(
+        anchor::ESCROW_DISCRIMINATOR,
+        [31, 213, 123, 187, 186, 22, 218, 155]
+    );
+
+    // 超过一个 SHA-256 块的名称
+    let long = IdlInstruction {
+        name: "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+        docs: "",
+        discriminator: 0,
+        accounts: &[],
+        args: &[],
+    };
+    assert_eq!(
+        long.anchor_discriminator(),
+        [122, 19, 115, 189, 153, 43, 155, 10]
+    );
+
+    // 每个 Anchor 判别符都映射回同名指令的单字节判别符
+    for (ix, (sighash, discriminator)) in idl::INSTRUCTIONS.iter().zip(&anchor::INSTRUCTIONS) {
+        assert_eq!(*sighash, ix.anchor_discriminator());
+        assert_eq!(*discriminator, ix.discriminator);
+        let mut data = sighash.to_vec();
+        data.push(7);
+        assert_eq!(
+            anchor::split_instruction(&data),
+            Some((discriminator, &[7][..]))
+        );
+    }
+    assert_eq!(anchor::split_instruction(&[*Make::DISCRIMINATOR; 9]), None);
+}
@@ -0,0 +1,328 @@
+/*
+从模糊输入构造合成账户。
+
+账户按运行时的内存布局分配 (`RuntimeAccount` 头部后紧跟数据，8 字节对齐，并预留
+`MAX_PERMITTED_DATA_INCREASE`)；地址与所有者取自一个固定地址池，其中包含本程序会校验的
+PDA / ATA，这样模糊器只需选对下标就能通过地址检查。数据可以是原始字节，也可以是
+按模板填好关键字段的 mint / 代币账户 / Escrow / Config / MintPolicy。
+ */
+
+use std::sync::OnceLock;
+
+use pinocchio::{
+    AccountView, Address,
+    account::{MAX_PERMITTED_DATA_INCREASE, NOT_BORROWED, RuntimeAccount},
+};
+use pinocchio_escrow::{
+    CONFIG_SEED, Config, ESCROW_SEED, EVENT_AUTHORITY_SEED, Escrow, MINT_POLICY_SEED, MintPolicy,
+    ProgramData,
+};
+
+const SYSTEM_PROGRAM_ID: Address = Address::new_from_array([0; 32]);
+const TOKEN_PROGRAM_ID: Address =
+    pinocchio::address::address!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID: Address =
+    pinocchio::address::address!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Address =
+    pinocchio::address::address!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// 单条指令最多合成的账户数
+pub const MAX_ACCOUNTS: usize = 20;
+
+/// 单个账户的 lamports 上限，保证总和远小于 `u64::MAX` (与链上总供应量同一量级)
+const MAX_LAMPORTS: u64 = 1 << 60;
+
+/// 顺序读取模糊输入，读完后一律返回 0
+pub struct Input<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn u8(&mut self) -> u8 {
+        let [first, rest @ ..] = self.bytes else {
+            return 0;
+        };
+        self.bytes = rest;
+        *first
+    }
+
+    pub fn u16(&mut self) -> u16 {
+        u16::from_le_bytes([self.u8(), self.u8()])
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(core::array::from_fn(|_| self.u8()))
+    }
+
+    pub fn bytes(&mut self, len: usize) -> &'a [u8] {
+        let (head, tail) = self.bytes.split_at(len.min(self.bytes.len()));
+        self.bytes = tail;
+        head
+    }
+
+    /// 剩余的全部字节 (作为指令数据)
+    pub fn rest(self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+/// 地址池：程序 ID、各系统程序、3 个钱包、2 个 mint，以及由它们推导出的 PDA / ATA
+pub struct Pool {
+    pub addresses: Vec<Address>,
+    pub wallets: [Address; 3],
+    pub mints: [Address; 2],
+    /// `wallets[0]` 以 seed 0 创建的托管 PDA
+    pub escrow: Address,
+    /// `escrow` 在 `mints[0]` / `mints[1]` 上的 ATA (金库 / 收益金库)
+    pub escrow_atas: [Address; 2],
+    pub config: Address,
+    pub event_authority: Address,
+}
+
+impl Pool {
+    pub const SYSTEM_PROGRAM_ID: Address = SYSTEM_PROGRAM_ID;
+    pub const TOKEN_PROGRAM_ID: Address = TOKEN_PROGRAM_ID;
+    pub const ASSOCIATED_TOKEN_PROGRAM_ID: Address = ASSOCIATED_TOKEN_PROGRAM_ID;
+
+    pub fn get() -> &'static Pool {
+        static POOL: OnceLock<Pool> = OnceLock::new();
+        POOL.get_or_init(Pool::new)
+    }
+
+    fn new() -> Self {
+        let wallets = [1u8, 2, 3].map(|i| Address::new_from_array([i; 32]));
+        let mints = [0xa0u8, 0xb0].map(|i| Address::new_from_array([i; 32]));
+        let pda = |seeds: &[&[u8]]| Address::find_program_address(seeds, &pinocchio_escrow::ID).0;
+
+        let escrow = pda(&[ESCROW_SEED, wallets[0].as_ref(), &0u64.to_le_bytes()]);
+        let escrow_atas = mints.map(|mint| ata(&escrow, &mint));
+        let config = pda(&[CONFIG_SEED]);
+        let event_authority = pda(&[EVENT_AUTHORITY_SEED]);
+
+        let mut addresses = vec![
+            pinocchio_escrow::ID,
+            SYSTEM_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            ProgramData::LOADER_ID,
+            Address::find_program_address(
+                &[pinocchio_escrow::ID.as_ref()],
+                &ProgramData::LOADER_ID,
+            )
+            .0,
+            escrow,
+            escrow_atas[0],
+            escrow_atas[1],
+            config,
+            event_authority,
+        ];
+        addresses.extend(wallets);
+        addresses.extend(mints);
+        for mint in &mints {
+            addresses.push(pda(&[MINT_POLICY_SEED, mint.as_ref()]));
+            for wallet in &wallets {
+                addresses.push(ata(wallet, mint));
+            }
+        }
+
+        Self {
+            addresses,
+            wallets,
+            mints,
+            escrow,
+            escrow_atas,
+            config,
+            event_authority,
+        }
+    }
+
+    /// 地址在池中的下标 (编码种子输入时使用)
+    pub fn index(&self, address: &Address) -> u8 {
+        self.addresses
+            .iter()
+            .position(|candidate| candidate == address)
+            .expect("address is not in the pool") as u8
+    }
+
+    pub fn pick(&self, input: &mut Input) -> Address {
+        self.addresses[input.u8() as usize % self.addresses.len()]
+    }
+
+    /// 多数情况下返回种子对应的真实 bump，偶尔取输入中的任意值
+    fn bump(&self, seeds: &[&[u8]], input: &mut Input) -> u8 {
+        match input.u8() % 4 {
+            0 => input.u8(),
+            _ => Address::find_program_address(seeds, &pinocchio_escrow::ID).1,
+        }
+    }
+}
+
+/// SPL Token 的关联代币账户地址
+pub fn ata(wallet: &Address, mint: &Address) -> Address {
+    Address::find_program_address(
+        &[wallet.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// 一组合成账户；`views` 引用 `buffers` 中的内存，重复的地址共用同一块内存 (与运行时一致)
+pub struct AccountSet {
+    _buffers: Vec<Vec<u64>>,
+    pub views: Vec<AccountView>,
+    /// 每个去重后账户的头部指针，用于统计 lamports
+    unique: Vec<*mut RuntimeAccount>,
+}
+
+impl AccountSet {
+    pub fn from_input(input: &mut Input) -> Self {
+        let pool = Pool::get();
+        let count = input.u8() as usize % (MAX_ACCOUNTS + 1);
+
+        let mut buffers: Vec<Vec<u64>> = Vec::with_capacity(count);
+        let mut unique: Vec<*mut RuntimeAccount> = Vec::with_capacity(count);
+        let mut views = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let flags = input.u8();
+            let address = pool.pick(input);
+
+            // 同一地址再次出现时复用已有账户
+            if let Some(&raw) = unique
+                .iter()
+                .find(|&&raw| unsafe { (*raw).address } == address)
+            {
+                views.push(unsafe { AccountView::new_unchecked(raw) });
+                continue;
+            }
+
+            let owner = pool.pick(input);
+            let lamports = input.u64() % MAX_LAMPORTS;
+            let data = account_data(pool, input);
+
+            let header = size_of::<RuntimeAccount>();
+            let words = (header + data.len() + MAX_PERMITTED_DATA_INCREASE).div_ceil(8);
+            let mut buffer = vec![0u64; words];
+            let raw = buffer.as_mut_ptr() as *mut RuntimeAccount;
+            unsafe {
+                raw.write(RuntimeAccount {
+                    borrow_state: NOT_BORROWED,
+                    is_signer: flags & 1,
+                    is_writable: (flags >> 1) & 1,
+                    executable: (flags >> 2) & 1,
+                    resize_delta: 0,
+                    address,
+                    owner,
+                    lamports,
+                    data_len: data.len() as u64,
+                });
+                let data_ptr = (raw as *mut u8).add(header);
+                core::ptr::copy_nonoverlapping(data.as_ptr(), data_ptr, data.len());
+            }
+
+            buffers.push(buffer);
+            unique.push(raw);
+            views.push(unsafe { AccountView::new_unchecked(raw) });
+        }
+
+        Self {
+            _buffers: buffers,
+            views,
+            unique,
+        }
+    }
+
+    /// 所有账户的 lamports 之和 (重复账户只计一次)
+    pub fn total_lamports(&self) -> u128 {
+        self.unique
+            .iter()
+            .map(|&raw| unsafe { (*raw).lamports } as u128)
+            .sum()
+    }
+}
+
+/// 按输入选择账户数据：原始字节或某种账户模板
+fn account_data(pool: &Pool, input: &mut Input) -> Vec<u8> {
+    match input.u8() % 8 {
+        0 => Vec::new(),
+        1 => {
+            let len = input.u8() as usize;
+            input.bytes(len).to_vec()
+        }
+        2 => mint(input),
+        3 => token_account(pool, input),
+        4 => escrow(pool, input),
+        5 => config(pool, input),
+        6 => mint_policy(pool, input),
+        _ => {
+            let mut data = input.bytes(Escrow::LEN).to_vec();
+            data.resize(Escrow::LEN, 0);
+            data
+        }
+    }
+}
+
+fn mint(input: &mut Input) -> Vec<u8> {
+    let mut data = vec![0u8; 82];
+    data[36..44].copy_from_slice(&input.u64().to_le_bytes()); // supply
+    data[44] = input.u8(); // decimals
+    data[45] = input.u8() % 2; // is_initialized
+    data
+}
+
+fn token_account(pool: &Pool, input: &mut Input) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data[0..32].copy_from_slice(pool.pick(input).as_ref()); // mint
+    data[32..64].copy_from_slice(pool.pick(input).as_ref()); // owner
+    data[64..72].copy_from_slice(&input.u64().to_le_bytes()); // amount
+    data[108] = input.u8() % 3; // state
+    data
+}
+
+fn escrow(pool: &Pool, input: &mut Input) -> Vec<u8> {
+    let mut bytes = [0u8; Escrow::LEN];
+    let escrow = Escrow::load_mut(&mut bytes).unwrap();
+
+    let seed = (input.u8() % 2) as u64;
+    let maker = pool.pick(input);
+    let bump = pool.bump(&[ESCROW_SEED, maker.as_ref(), &seed.to_le_bytes()], input);
+    escrow.set_inner(
+        seed,
+        maker,
+        pool.pick(input),
+        pool.pick(input),
+        input.u64(),
+        [bump],
+    );
+    escrow.set_referral_bps(input.u16() % (pinocchio_escrow::MAX_BPS + 1));
+    escrow.set_rent_recipient(pool.pick(input));
+    escrow.set_proceeds_recipient(pool.pick(input));
+    escrow.set_settlement(input.u8() % 3);
+    escrow.set_status(input.u8() % 3);
+    bytes.to_vec()
+}
+
+fn config(pool: &Pool, input: &mut Input) -> Vec<u8> {
+    let mut data = vec![0u8; Config::LEN];
+    data[0..32].copy_from_slice(pool.pick(input).as_ref()); // admin
+    if input.u8() % 2 == 1 {
+        data[32..64].copy_from_slice(pool.pick(input).as_ref()); // pending_admin
+    }
+    data[64] = input.u8(); // paused
+    data[65] = pool.bump(&[CONFIG_SEED], input);
+    data
+}
+
+fn mint_policy(pool: &Pool, input: &mut Input) -> Vec<u8> {
+    let mut data = vec![0u8; MintPolicy::LEN];
+    let mint = pool.mints[input.u8() as usize % pool.mints.len()];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32] = input.u8() % 3; // status
+    data[33] = pool.bump(&[MINT_POLICY_SEED, mint.as_ref()], input);
+    data
+}
@@ -0,0 +1,218 @@
+/*
+pinocchio_escrow 的模糊测试入口，供 `fuzz/fuzz_targets` 与语料回放测试共用。
+
+- `instruction_data`：把任意字节交给指令数据 / 账户数据 / 事件的解析函数；
+- `process_instruction`：从输入合成一组账户与指令数据，在链下直接调用 `process_instruction`。
+
+链下编译时 CPI 与 syscall 均为空操作，因此这里检查的是程序自身的逻辑；
+SPL Token 的 Transfer / CloseAccount 由程序记入 `cpi_trace` (依赖程序的 `fuzz` feature)，执行后逐条检查。不变量：
+
+1. 任何输入都不会 panic；
+2. lamports 总量不变 (关闭账户只转移，不会凭空产生)；
+3. 指令成功时，其 `ACCOUNTS` 表中标记为签名者的位置必须确实已签名，
+   即不存在绕过签名就能转走代币或改动状态的路径；
+4. 指令成功时，每次 Transfer / CloseAccount 的 authority 要么已签名，要么就是以正确种子签名的托管 PDA，
+   且被转出 / 关闭的代币账户的 mint 是该托管的 mint_a 或 mint_b。
+ */
+
+pub mod accounts;
+pub use accounts::*;
+
+pub mod seeds;
+
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio_escrow::{
+    AcceptAdmin, AccountSpec, Claim, Config, EmitEvent, Escrow, EscrowVersion, Event, InitConfig,
+    MAX_BPS, Make, MakeInstructionData, MintPolicy, Quote, QuoteOffer, Refund, SetMintPolicy,
+    SetPause, SettledAmounts, Take, TakeInstructionData, TransferAdmin, cpi_trace,
+};
+
+/// 解析函数：只要求不 panic，且能解析的输入在重新编码后保持不变
+pub fn instruction_data(data: &[u8]) {
+    if let Ok(args) = MakeInstructionData::try_from(data) {
+        assert_ne!(args.amount, 0);
+        assert!(args.referral_bps <= MAX_BPS);
+
+        // 完整编码后再次解析，缺省的收款人 / 租金接收者以 maker 填充
+        let maker = Address::new_from_array([0x4d; 32]);
+        let again = MakeInstructionData::try_from(&args.to_bytes(&maker)[..]).unwrap();
+        assert_eq!(
+            (again.seed, again.receive, again.amount, again.referral_bps),
+            (args.seed, args.receive, args.amount, args.referral_bps)
+        );
+        assert_eq!(
+            again.rent_recipient,
+            Some(args.rent_recipient.unwrap_or(maker))
+        );
+        assert_eq!(
+            again.proceeds_recipient,
+            Some(args.proceeds_recipient.unwrap_or(maker))
+        );
+        assert_eq!(again.settlement, args.settlement);
+    }
+
+    // Take 参数只有空数据与完整编码两种长度
+    if let Ok(args) = TakeInstructionData::try_from(data) {
+        let bytes = args.to_bytes();
+        if data.is_empty() {
+            assert_eq!(bytes, TakeInstructionData::UNCHECKED.to_bytes());
+        } else {
+            assert_eq!(bytes[..], data[..]);
+        }
+    }
+
+    // 状态布局按 1 字节对齐，任意偏移的字节都可以直接读取
+    let bytes = data;
+
+    assert_eq!(
+        Escrow::load(bytes).is_ok(),
+        data.len() == Escrow::LEN && data.starts_with(Escrow::DISCRIMINATOR)
+    );
+    assert_eq!(Config::load(bytes).is_ok(), data.len() == Config::LEN);
+    assert_eq!(
+        MintPolicy::load(bytes).is_ok(),
+        data.len() == MintPolicy::LEN
+    );
+
+    // Make 保证 referral_bps 不超过 MAX_BPS，只在满足该前提的未成交报价上检查定价
+    if let Ok(escrow) = EscrowVersion::load(bytes)
+        && escrow.referral_bps() <= MAX_BPS
+        && !escrow.is_filled()
+    {
+        let vault_amount = escrow.receive().rotate_left(17);
+        for with_referrer in [false, true] {
+            let quote = Quote::new(&escrow, vault_amount, with_referrer);
+            assert_eq!(quote.amount_in, escrow.receive());
+            assert_eq!(quote.maker_amount() + quote.fee, quote.amount_in);
+        }
+    }
+
+    if let Ok(quote) = Quote::from_bytes(data) {
+        assert_eq!(
+            &quote.to_bytes()[..SettledAmounts::LEN],
+            &data[..SettledAmounts::LEN]
+        );
+    }
+    if let Ok(settled) = SettledAmounts::from_bytes(data) {
+        assert_eq!(&settled.to_bytes()[..], data);
+    }
+    if let Ok(event) = Event::decode(data) {
+        let encoded = match event {
+            Event::OfferMade(event) => event.to_bytes().to_vec(),
+            Event::OfferTaken(event) => event.to_bytes().to_vec(),
+            Event::OfferRefunded(event) => event.to_bytes().to_vec(),
+            Event::ProceedsClaimed(event) => event.to_bytes().to_vec(),
+        };
+        assert_eq!(encoded, data);
+    }
+}
+
+/// 用合成账户执行一条指令并检查不变量，返回程序的执行结果
+pub fn process_instruction(data: &[u8]) -> ProgramResult {
+    let mut input = Input::new(data);
+    let accounts = AccountSet::from_input(&mut input);
+    let instruction_data = input.rest();
+
+    let specs = instruction_data.first().and_then(|&d| accounts_of(d));
+    let escrow = specs.and_then(|specs| escrow_of(specs, &accounts.views));
+    let mints_before = escrow.and_then(escrow_mints);
+
+    let lamports = accounts.total_lamports();
+    cpi_trace::take();
+    let result = pinocchio_escrow::process_instruction(
+        &pinocchio_escrow::ID,
+        &accounts.views,
+        instruction_data,
+    );
+    assert_eq!(
+        accounts.total_lamports(),
+        lamports,
+        "lamports created or destroyed"
+    );
+    let token_cpis = cpi_trace::take();
+
+    if result.is_ok()
+        && let Some(specs) = specs
+    {
+        for (index, spec) in specs.iter().enumerate() {
+            assert!(
+                !spec.signer || accounts.views[index].is_signer(),
+                "instruction {} succeeded without `{}` signing",
+                instruction_data[0],
+                spec.name
+            );
+        }
+    }
+
+    if result.is_ok() && !token_cpis.is_empty() {
+        // Make 在执行中写入托管，其余指令可能已关闭托管，因此优先用执行前的快照
+        let escrow = escrow.expect("token CPI from an instruction without an escrow");
+        let (mint_a, mint_b) = mints_before
+            .or_else(|| escrow_mints(escrow))
+            .expect("token CPI without a readable escrow");
+
+        for cpi in &token_cpis {
+            let authority = find(&accounts.views, &cpi.authority);
+            assert!(
+                authority.is_signer() || cpi.pda_signer.as_ref() == Some(&cpi.authority),
+                "{cpi:?} is neither signed by its authority nor by its PDA"
+            );
+            if let Some(pda) = &cpi.pda_signer {
+                assert_eq!(pda, escrow.address(), "{cpi:?} signed for another PDA");
+            }
+
+            let mint = token_mint(find(&accounts.views, &cpi.source))
+                .unwrap_or_else(|| panic!("{cpi:?} moves a non-token account"));
+            assert!(
+                mint == mint_a || mint == mint_b,
+                "{cpi:?} moves a mint other than the escrow's"
+            );
+        }
+    }
+
+    result
+}
+
+/// 固定账户中名为 `escrow` 的账户
+fn escrow_of<'a>(specs: &[AccountSpec], views: &'a [AccountView]) -> Option<&'a AccountView> {
+    let index = specs.iter().position(|spec| spec.name == "escrow")?;
+    views.get(index)
+}
+
+/// 托管记录中的 `(mint_a, mint_b)` (两种布局都接受)
+fn escrow_mints(escrow: &AccountView) -> Option<(Address, Address)> {
+    let data = escrow.try_borrow().ok()?;
+    let escrow = EscrowVersion::load(&data).ok()?;
+    Some((*escrow.mint_a(), *escrow.mint_b()))
+}
+
+/// SPL Token 账户的 mint
+fn token_mint(account: &AccountView) -> Option<Address> {
+    let data = account.try_borrow().ok()?;
+    (data.len() == 165).then(|| Address::new_from_array(data[..32].try_into().unwrap()))
+}
+
+fn find<'a>(views: &'a [AccountView], address: &Address) -> &'a AccountView {
+    views
+        .iter()
+        .find(|view| view.address() == address)
+        .expect("CPI account not passed to the instruction")
+}
+
+/// 判别符对应指令的固定账户表
+fn accounts_of(discriminator: u8) -> Option<&'static [AccountSpec]> {
+    Some(match discriminator {
+        d if d == *Make::DISCRIMINATOR => &Make::ACCOUNTS,
+        d if d == *Take::DISCRIMINATOR => &Take::ACCOUNTS,
+        d if d == *Refund::DISCRIMINATOR => &Refund::ACCOUNTS,
+        d if d == *Claim::DISCRIMINATOR => &Claim::ACCOUNTS,
+        d if d == *QuoteOffer::DISCRIMINATOR => &QuoteOffer::ACCOUNTS,
+        d if d == *InitConfig::DISCRIMINATOR => &InitConfig::ACCOUNTS,
+        d if d == *SetPause::DISCRIMINATOR => &SetPause::ACCOUNTS,
+        d if d == *TransferAdmin::DISCRIMINATOR => &TransferAdmin::ACCOUNTS,
+        d if d == *AcceptAdmin::DISCRIMINATOR => &AcceptAdmin::ACCOUNTS,
+        d if d == *SetMintPolicy::DISCRIMINATOR => &SetMintPolicy::ACCOUNTS,
+        d if d == *EmitEvent::DISCRIMINATOR => &EmitEvent::ACCOUNTS,
+        _ => return None,
+    })
+}
@@ -0,0 +1,416 @@
+/*
+种子输入：按 `AccountSet::from_input` 的格式编码出能走通各条指令的账户集合。
+
+模糊器从这些输入出发变异，比从随机字节起步更容易覆盖到成功路径；
+回放测试同时检查它们确实成功，防止解码格式或程序校验变化后种子悄悄失效。
+
+链下读取 Rent sysvar 会返回 `UnsupportedSysvar`，Make 因此止步于创建托管账户之前；
+它的种子仍能覆盖全部账户与参数校验，期望结果即为该错误。
+ */
+
+use pinocchio::{Address, ProgramResult, error::ProgramError};
+use pinocchio_escrow::{
+    AccountFlagError, Claim, Escrow, EscrowError, Make, MakeInstructionData, QuoteOffer, Refund,
+    SetPause, Take, TakeInstructionData,
+};
+
+use crate::{Pool, ata};
+
+/// 账户数据模板，与 `accounts::account_data` 的分支一一对应
+pub enum Data {
+    Empty,
+    Mint,
+    Token {
+        mint: Address,
+        owner: Address,
+        amount: u64,
+    },
+    Escrow {
+        maker: Address,
+        mint_a: Address,
+        mint_b: Address,
+        receive: u64,
+        referral_bps: u16,
+        settlement: u8,
+        status: u8,
+    },
+    Config {
+        admin: Address,
+        paused: u8,
+    },
+}
+
+pub struct Account {
+    pub address: Address,
+    pub signer: bool,
+    pub writable: bool,
+    pub executable: bool,
+    pub owner: Address,
+    pub lamports: u64,
+    pub data: Data,
+}
+
+impl Account {
+    fn new(address: Address, owner: Address, data: Data) -> Self {
+        Self {
+            address,
+            signer: false,
+            writable: false,
+            executable: false,
+            owner,
+            lamports: 1_000_000_000,
+            data,
+        }
+    }
+
+    fn wallet(address: Address) -> Self {
+        Self::new(address, Pool::SYSTEM_PROGRAM_ID, Data::Empty)
+    }
+
+    fn program(address: Address) -> Self {
+        Self {
+            executable: true,
+            ..Self::new(address, Pool::SYSTEM_PROGRAM_ID, Data::Empty)
+        }
+    }
+
+    fn signer(self) -> Self {
+        Self {
+            signer: true,
+            writable: true,
+            ..self
+        }
+    }
+
+    fn writable(self) -> Self {
+        Self {
+            writable: true,
+            ..self
+        }
+    }
+}
+
+/// 编码账户与指令数据；重复的地址只写标志与下标，与解码端复用账户的逻辑一致
+pub fn encode(accounts: &[Account], instruction_data: &[u8]) -> Vec<u8> {
+    let pool = Pool::get();
+    let mut bytes = vec![accounts.len() as u8];
+
+    for (i, account) in accounts.iter().enumerate() {
+        bytes.push(
+            account.signer as u8 | (account.writable as u8) << 1 | (account.executable as u8) << 2,
+        );
+        bytes.push(pool.index(&account.address));
+        if accounts[..i].iter().any(|a| a.address == account.address) {
+            continue;
+        }
+
+        bytes.push(pool.index(&account.owner));
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        match &account.data {
+            Data::Empty => bytes.push(0),
+            Data::Mint => {
+                bytes.push(2);
+                bytes.extend_from_slice(&0u64.to_le_bytes()); // supply
+                bytes.extend_from_slice(&[6, 1]); // decimals, is_initialized
+            }
+            Data::Token {
+                mint,
+                owner,
+                amount,
+            } => {
+                bytes.extend_from_slice(&[3, pool.index(mint), pool.index(owner)]);
+                bytes.extend_from_slice(&amount.to_le_bytes());
+                bytes.push(1); // AccountState::Initialized
+            }
+            Data::Escrow {
+                maker,
+                mint_a,
+                mint_b,
+                receive,
+                referral_bps,
+                settlement,
+                status,
+            } => {
+                // seed 0，bump 取真实值
+                bytes.extend_from_slice(&[4, 0, pool.index(maker), 1]);
+                bytes.extend_from_slice(&[pool.index(mint_a), pool.index(mint_b)]);
+                bytes.extend_from_slice(&receive.to_le_bytes());
+                bytes.extend_from_slice(&referral_bps.to_le_bytes());
+                // 租金接收者与收款人均为 maker
+                bytes.extend_from_slice(&[pool.index(maker), pool.index(maker)]);
+                bytes.extend_from_slice(&[*settlement, *status]);
+            }
+            Data::Config { admin, paused } => {
+                bytes.extend_from_slice(&[5, pool.index(admin), 0, *paused, 1]);
+            }
+        }
+    }
+
+    bytes.extend_from_slice(instruction_data);
+    bytes
+}
+
+/// `process_instruction` 目标的种子，附带期望的执行结果
+pub struct Seed {
+    pub name: &'static str,
+    pub input: Vec<u8>,
+    pub expect: ProgramResult,
+}
+
+pub fn process_instruction_seeds() -> Vec<Seed> {
+    let pool = Pool::get();
+    let [maker, taker, admin] = pool.wallets;
+    let [mint_a, mint_b] = pool.mints;
+    let [vault, proceeds_vault] = pool.escrow_atas;
+    let token = Pool::TOKEN_PROGRAM_ID;
+
+    let token_account = |mint: Address, owner: Address, amount: u64| {
+        Account::new(
+            ata(&owner, &mint),
+            token,
+            Data::Token {
+                mint,
+                owner,
+                amount,
+            },
+        )
+        .writable()
+    };
+    let escrow = |settlement: u8, status: u8| {
+        Account::new(
+            pool.escrow,
+            pinocchio_escrow::ID,
+            Data::Escrow {
+                maker,
+                mint_a,
+                mint_b,
+                receive: 5_000,
+                referral_bps: 0,
+                settlement,
+                status,
+            },
+        )
+        .writable()
+    };
+    let vault_account = |mint: Address, address: Address, amount: u64| {
+        Account::new(
+            address,
+            token,
+            Data::Token {
+                mint,
+                owner: pool.escrow,
+                amount,
+            },
+        )
+        .writable()
+    };
+    let config = || {
+        Account::new(
+            pool.config,
+            pinocchio_escrow::ID,
+            Data::Config { admin, paused: 0 },
+        )
+    };
+    let mint = |address: Address| Account::new(address, token, Data::Mint);
+    let programs = || {
+        [
+            Account::program(Pool::SYSTEM_PROGRAM_ID),
+            Account::program(token),
+            Account::program(Pool::ASSOCIATED_TOKEN_PROGRAM_ID),
+        ]
+    };
+
+    let make = {
+        let [system, token_program, ata_program] = programs();
+        let args = MakeInstructionData {
+            seed: 0,
+            receive: 5_000,
+            amount: 1_000,
+            referral_bps: 0,
+            rent_recipient: None,
+            proceeds_recipient: None,
+            settlement: Escrow::SETTLE_PUSH,
+        };
+        let mut data = vec![*Make::DISCRIMINATOR];
+        data.extend_from_slice(&args.to_bytes(&maker));
+        encode(
+            &[
+                Account::wallet(maker).signer(),
+                Account::new(pool.escrow, Pool::SYSTEM_PROGRAM_ID, Data::Empty).writable(),
+                mint(mint_a),
+                mint(mint_b),
+                token_account(mint_a, maker, 1_000),
+                Account::new(vault, Pool::SYSTEM_PROGRAM_ID, Data::Empty).writable(),
+                system,
+                token_program,
+                ata_program,
+                config(),
+            ],
+            &data,
+        )
+    };
+
+    // 金库 1_000 Token A，售价 5_000 Token B
+    let limits = |max_amount_in: u64| TakeInstructionData {
+        max_amount_in,
+        min_amount_out: 1_000,
+    };
+    let take =
+        |vault_writable: bool, referrer: Option<Account>, args: Option<TakeInstructionData>| {
+            let [system, token_program, ata_program] = programs();
+            let mut accounts = vec![
+                Account::wallet(taker).signer(),
+                // 重复账户复用首次出现的标志，maker 同时是 (可写的) 租金接收者
+                Account::wallet(maker).writable(),
+                escrow(Escrow::SETTLE_PUSH, Escrow::STATUS_OPEN),
+                mint(mint_a),
+                mint(mint_b),
+                Account {
+                    writable: vault_writable,
+                    ..vault_account(mint_a, vault, 1_000)
+                },
+                token_account(mint_a, taker, 0),
+                token_account(mint_b, taker, 5_000),
+                token_account(mint_b, maker, 0),
+                system,
+                token_program,
+                ata_program,
+                Account::wallet(maker).writable(),
+                Account::wallet(maker),
+                config(),
+            ];
+            accounts.extend(referrer);
+            // 不带参数即旧客户端的 v0 编码
+            let mut data = vec![*Take::DISCRIMINATOR];
+            data.extend(args.map(|args| args.to_bytes()).into_iter().flatten());
+            encode(&accounts, &data)
+        };
+
+    let refund = |signer: bool| {
+        let [system, token_program, ata_program] = programs();
+        encode(
+            &[
+                Account {
+                    signer,
+                    ..Account::wallet(maker).writable()
+                },
+                escrow(Escrow::SETTLE_PUSH, Escrow::STATUS_OPEN),
+                mint(mint_a),
+                vault_account(mint_a, vault, 1_000),
+                token_account(mint_a, maker, 0),
+                ata_program,
+                token_program,
+                system,
+                Account::wallet(maker).writable(),
+            ],
+            &[*Refund::DISCRIMINATOR],
+        )
+    };
+
+    let claim = {
+        let [system, token_program, ata_program] = programs();
+        encode(
+            &[
+                Account::wallet(maker).signer(),
+                escrow(Escrow::SETTLE_CLAIM, Escrow::STATUS_FILLED),
+                mint(mint_b),
+                vault_account(mint_b, proceeds_vault, 5_000),
+                token_account(mint_b, maker, 0),
+                Account::wallet(maker),
+                Account::wallet(maker).writable(),
+                system,
+                token_program,
+                ata_program,
+            ],
+            &[*Claim::DISCRIMINATOR],
+        )
+    };
+
+    let quote = encode(
+        &[
+            escrow(Escrow::SETTLE_PUSH, Escrow::STATUS_OPEN),
+            vault_account(mint_a, vault, 1_000),
+        ],
+        &[*QuoteOffer::DISCRIMINATOR],
+    );
+
+    let set_pause = encode(
+        &[Account::wallet(admin).signer(), config().writable()],
+        &[*SetPause::DISCRIMINATOR, 1],
+    );
+
+    let seed = |name, input, expect| Seed {
+        name,
+        input,
+        expect,
+    };
+    vec![
+        seed("make", make, Err(ProgramError::UnsupportedSysvar)),
+        seed("take", take(true, None, Some(limits(5_000))), Ok(())),
+        seed("take-v0", take(true, None, None), Ok(())),
+        seed(
+            "take-slippage",
+            take(true, None, Some(limits(4_999))),
+            Err(EscrowError::SlippageExceeded.into()),
+        ),
+        // 推荐人位置传入程序 ID，视为未提供
+        seed(
+            "take-none-referrer",
+            take(
+                true,
+                Some(Account::program(pinocchio_escrow::ID)),
+                Some(limits(5_000)),
+            ),
+            Ok(()),
+        ),
+        seed(
+            "take-readonly-vault",
+            take(false, None, Some(limits(5_000))),
+            Err(ProgramError::Custom(
+                AccountFlagError::NotWritable(5).code(),
+            )),
+        ),
+        seed("refund", refund(true), Ok(())),
+        seed(
+            "refund-without-signer",
+            refund(false),
+            Err(ProgramError::Custom(
+                AccountFlagError::MissingSigner(0).code(),
+            )),
+        ),
+        seed("claim", claim, Ok(())),
+        seed("quote", quote, Ok(())),
+        seed("set-pause", set_pause, Ok(())),
+    ]
+}
+
+/// `instruction_data` 目标的种子：各长度的 Make 参数、Take 参数与一个完整的 Escrow
+pub fn instruction_data_seeds() -> Vec<(&'static str, Vec<u8>)> {
+    let args = MakeInstructionData {
+        seed: 7,
+        receive: 5_000,
+        amount: 1_000,
+        referral_bps: 250,
+        rent_recipient: None,
+        proceeds_recipient: None,
+        settlement: Escrow::SETTLE_CLAIM,
+    };
+    let full = args.to_bytes(&Pool::get().wallets[0]);
+
+    vec![
+        ("make-base", full[..24].to_vec()),
+        ("make-referral", full[..26].to_vec()),
+        ("make-recipients", full[..90].to_vec()),
+        ("make-full", full.to_vec()),
+        (
+            "take-limits",
+            TakeInstructionData {
+                max_amount_in: 5_000,
+                min_amount_out: 1_000,
+            }
+            .to_bytes()
+            .to_vec(),
+        ),
+        ("escrow", vec![0x11; Escrow::LEN]),
+    ]
+}
@@ -0,0 +1,78 @@
+//! 回放种子与已提交的语料，作为普通的回归测试运行
+//!
+//! 模糊器发现的崩溃输入经 `cargo fuzz tmin` 最小化后同样提交到 `fuzz/corpus/<target>/`。
+//!
+//! `UPDATE_FUZZ_CORPUS=1` 时把种子写入 `fuzz/corpus/<target>/seed-<name>`。
+
+use std::{fs, path::PathBuf};
+
+use escrow_fuzz_harness::seeds::{instruction_data_seeds, process_instruction_seeds};
+
+const FUZZ_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../fuzz");
+
+/// `fuzz/corpus/<target>` 下的全部输入
+fn checked_in(target: &str) -> Vec<(PathBuf, Vec<u8>)> {
+    fs::read_dir(PathBuf::from(FUZZ_DIR).join("corpus").join(target))
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .map(|path| {
+            let bytes = fs::read(&path).unwrap();
+            (path, bytes)
+        })
+        .collect()
+}
+
+fn write_seeds(target: &str, seeds: &[(&str, Vec<u8>)]) {
+    if std::env::var("UPDATE_FUZZ_CORPUS").is_err() {
+        return;
+    }
+    let dir = PathBuf::from(FUZZ_DIR).join("corpus").join(target);
+    fs::create_dir_all(&dir).unwrap();
+    for (name, bytes) in seeds {
+        fs::write(dir.join(format!("seed-{name}")), bytes).unwrap();
+    }
+}
+
+#[test]
+fn process_instruction_seeds_behave() {
+    let seeds = process_instruction_seeds();
+    let inputs: Vec<_> = seeds.iter().map(|s| (s.name, s.input.clone())).collect();
+    write_seeds("process_instruction", &inputs);
+
+    for seed in &seeds {
+        assert_eq!(
+            escrow_fuzz_harness::process_instruction(&seed.input),
+            seed.expect,
+            "{}",
+            seed.name
+        );
+    }
+}
+
+#[test]
+fn instruction_data_seeds_behave() {
+    let seeds = instruction_data_seeds();
+    write_seeds("instruction_data", &seeds);
+
+    for (_, input) in &seeds {
+        escrow_fuzz_harness::instruction_data(input);
+    }
+}
+
+#[test]
+fn replay_process_instruction_corpus() {
+    for (path, input) in checked_in("process_instruction") {
+        eprintln!("{}", path.display());
+        let _ = escrow_fuzz_harness::process_instruction(&input);
+    }
+}
+
+#[test]
+fn replay_instruction_data_corpus() {
+    for (path, input) in checked_in("instruction_data") {
+        eprintln!("{}", path.display());
+        escrow_fuzz_harness::instruction_data(&input);
+    }
+}
@@ -0,0 +1,86 @@
+/*
+将 `pinocchio_escrow::idl` 中的描述渲染为 Anchor 0.30 格式的 JSON IDL。
+
+生成结果提交在 `idl/pinocchio_escrow.json`，测试会核对其与当前代码一致；
+修改程序后运行 `cargo run -p escrow-idl > idl/pinocchio_escrow.json` 重新生成。
+程序以 `anchor` feature 部署时加上 `--features anchor`，指令与 Escrow 改用 Anchor 的 8 字节判别符。
+ */
+
+use pinocchio_escrow::{
+    anchor,
+    idl::{self, IdlField, IdlLayout},
+};
+use serde_json::{Map, Value, json};
+
+/// 生成完整的 IDL
+pub fn render() -> Value {
+    json!({
+        "address": pinocchio_escrow::ID.to_string(),
+        "metadata": {
+            "name": idl::NAME,
+            "version": idl::VERSION,
+            "spec": "0.1.0",
+            "description": "Token escrow written with pinocchio",
+        },
+        "instructions": idl::INSTRUCTIONS
+            .iter()
+            .map(|ix| json!({
+                "name": ix.name,
+                "docs": [ix.docs],
+                "discriminator": if anchor::ENABLED {
+                    ix.anchor_discriminator().to_vec()
+                } else {
+                    vec![ix.discriminator]
+                },
+                "accounts": ix.accounts.iter().map(|account| {
+                    let mut meta = Map::new();
+                    meta.insert("name".into(), account.name.into());
+                    // Anchor 只在为 true 时写出标志
+                    if account.writable {
+                        meta.insert("writable".into(), true.into());
+                    }
+                    if account.signer {
+                        meta.insert("signer".into(), true.into());
+                    }
+                    Value::Object(meta)
+                }).collect::<Vec<_>>(),
+                "args": fields(ix.args),
+            }))
+            .collect::<Vec<_>>(),
+        "accounts": declarations(idl::ACCOUNTS),
+        "events": declarations(idl::EVENTS),
+        "errors": idl::ERRORS
+            .iter()
+            .map(|error| json!({ "code": error.code, "name": error.name, "msg": error.msg }))
+            .collect::<Vec<_>>(),
+        "types": idl::ACCOUNTS
+            .iter()
+            .chain(idl::EVENTS)
+            .map(|layout| json!({
+                "name": layout.name,
+                "type": { "kind": "struct", "fields": fields(layout.fields) },
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// 渲染为带换行的 JSON 文本 (与提交的文件逐字节一致)
+pub fn render_pretty() -> String {
+    let mut text = serde_json::to_string_pretty(&render()).expect("IDL is valid JSON");
+    text.push('\n');
+    text
+}
+
+fn fields(fields: &[IdlField]) -> Vec<Value> {
+    fields
+        .iter()
+        .map(|field| json!({ "name": field.name, "type": field.ty.name() }))
+        .collect()
+}
+
+fn declarations(layouts: &[IdlLayout]) -> Vec<Value> {
+    layouts
+        .iter()
+        .map(|layout| json!({ "name": layout.name, "discriminator": layout.discriminator }))
+        .collect()
+}
@@ -0,0 +1,3 @@
+fn main() {
+    print!("{}", escrow_idl::render_pretty());
+}
@@ -0,0 +1,209 @@
+//! 核对 IDL 与程序的实际解析逻辑一致
+
+use std::{collections::HashMap, fs, path::Path};
+
+use pinocchio_escrow::{Escrow, EscrowError, Make, MakeInstructionData, anchor};
+use serde_json::Value;
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/pinocchio_escrow.json");
+
+fn type_size(ty: &str) -> usize {
+    match ty {
+        "u8" | "bool" => 1,
+        "u16" => 2,
+        "u64" => 8,
+        "pubkey" => 32,
+        other => panic!("unexpected IDL type {other}"),
+    }
+}
+
+/// 按 IDL 字段顺序编码，数值取自 `values` (地址字段以单字节重复 32 次表示)
+fn encode(fields: &Value, values: &HashMap<&str, u64>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for field in fields.as_array().unwrap() {
+        let value = values[field["name"].as_str().unwrap()];
+        match field["type"].as_str().unwrap() {
+            "u8" | "bool" => bytes.push(value as u8),
+            "u16" => bytes.extend_from_slice(&(value as u16).to_le_bytes()),
+            "u64" => bytes.extend_from_slice(&value.to_le_bytes()),
+            "pubkey" => bytes.extend_from_slice(&[value as u8; 32]),
+            other => panic!("unexpected IDL type {other}"),
+        }
+    }
+    bytes
+}
+
+fn instruction<'a>(idl: &'a Value, name: &str) -> &'a Value {
+    idl["instructions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|ix| ix["name"] == name)
+        .unwrap()
+}
+
+/// IDL 中的判别符字节
+fn discriminator(value: &Value) -> Vec<u8> {
+    value["discriminator"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|byte| byte.as_u64().unwrap() as u8)
+        .collect()
+}
+
+fn type_fields<'a>(idl: &'a Value, name: &str) -> &'a Value {
+    &idl["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|ty| ty["name"] == name)
+        .unwrap()["type"]["fields"]
+}
+
+#[test]
+#[cfg_attr(
+    feature = "anchor",
+    ignore = "the checked-in IDL uses single-byte discriminators"
+)]
+fn checked_in_idl_is_up_to_date() {
+    let generated = escrow_idl::render_pretty();
+    if std::env::var("UPDATE_IDL").is_ok() {
+        fs::write(IDL_PATH, &generated).unwrap();
+    }
+
+    let checked_in = fs::read_to_string(Path::new(IDL_PATH)).unwrap();
+    assert!(
+        checked_in == generated,
+        "idl/pinocchio_escrow.json is stale; run `cargo run -p escrow-idl > idl/pinocchio_escrow.json`"
+    );
+}
+
+#[test]
+fn make_args_match_program_parser() {
+    let idl = escrow_idl::render();
+    let make = instruction(&idl, "make");
+    // Anchor 模式下为 8 字节判别符，程序将其映射回 Make
+    let prefix = discriminator(make);
+    if anchor::ENABLED {
+        assert_eq!(
+            anchor::split_instruction(&prefix),
+            Some((Make::DISCRIMINATOR, &[][..]))
+        );
+    } else {
+        assert_eq!(prefix, [*Make::DISCRIMINATOR]);
+    }
+
+    let values = HashMap::from([
+        ("seed", 11),
+        ("receive", 2_000),
+        ("amount", 300),
+        ("referral_bps", 125),
+        ("rent_recipient", 7),
+        ("proceeds_recipient", 8),
+        ("settlement", Escrow::SETTLE_CLAIM as u64),
+    ]);
+    let bytes = encode(&make["args"], &values);
+    assert_eq!(bytes.len(), MakeInstructionData::LEN);
+
+    let parsed = MakeInstructionData::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(parsed.seed, 11);
+    assert_eq!(parsed.receive, 2_000);
+    assert_eq!(parsed.amount, 300);
+    assert_eq!(parsed.referral_bps, 125);
+    assert_eq!(parsed.rent_recipient.unwrap().as_ref(), &[7; 32]);
+    assert_eq!(parsed.proceeds_recipient.unwrap().as_ref(), &[8; 32]);
+    assert_eq!(parsed.settlement, Escrow::SETTLE_CLAIM);
+
+    // 文档中声明的截断点都必须被接受
+    let mut offset = 0;
+    let mut cut_points = Vec::new();
+    for field in make["args"].as_array().unwrap() {
+        offset += type_size(field["type"].as_str().unwrap());
+        if matches!(
+            field["name"].as_str(),
+            Some("amount" | "referral_bps" | "proceeds_recipient")
+        ) {
+            cut_points.push(offset);
+        }
+    }
+    for len in cut_points {
+        assert!(
+            MakeInstructionData::try_from(&bytes[..len]).is_ok(),
+            "len {len}"
+        );
+    }
+}
+
+#[test]
+fn escrow_layout_matches_program() {
+    let idl = escrow_idl::render();
+    let fields = type_fields(&idl, "Escrow");
+
+    let len: usize = fields
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| type_size(field["type"].as_str().unwrap()))
+        .sum();
+    let account = idl["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|account| account["name"] == "Escrow")
+        .unwrap();
+    let prefix = discriminator(account);
+    assert_eq!(prefix.len() + len, Escrow::LEN);
+
+    let values = HashMap::from([
+        ("seed", 5),
+        ("maker", 1),
+        ("mint_a", 2),
+        ("mint_b", 3),
+        ("receive", 900),
+        ("bump", 254),
+        ("referral_bps", 42),
+        ("rent_recipient", 4),
+        ("proceeds_recipient", 6),
+        ("settlement", Escrow::SETTLE_CLAIM as u64),
+        ("status", Escrow::STATUS_FILLED as u64),
+    ]);
+    let mut data = [0u8; Escrow::LEN];
+    data.copy_from_slice(&[prefix, encode(fields, &values)].concat());
+
+    let escrow = Escrow::load(&data).unwrap();
+    assert_eq!(escrow.seed(), 5);
+    assert_eq!(escrow.maker.as_ref(), &[1; 32]);
+    assert_eq!(escrow.mint_b.as_ref(), &[3; 32]);
+    assert_eq!(escrow.receive(), 900);
+    assert_eq!(escrow.bump, [254]);
+    assert_eq!(escrow.referral_bps(), 42);
+    assert_eq!(escrow.proceeds_recipient.as_ref(), &[6; 32]);
+    assert!(escrow.is_claim_mode());
+    assert!(escrow.is_filled());
+}
+
+#[test]
+fn error_codes_are_unique_and_contiguous() {
+    let idl = escrow_idl::render();
+    let codes: Vec<u64> = idl["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["code"].as_u64().unwrap())
+        .collect();
+    assert_eq!(codes, (0..codes.len() as u64).collect::<Vec<_>>());
+}
+
+#[test]
+fn every_escrow_error_is_listed_by_code() {
+    let idl = escrow_idl::render();
+    let errors = idl["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), EscrowError::ALL.len());
+    for &error in EscrowError::ALL {
+        let entry = &errors[error as usize];
+        assert_eq!(entry["code"], error as u32);
+        assert_eq!(entry["name"], format!("{error:?}"));
+        assert!(!entry["msg"].as_str().unwrap().is_empty());
+    }
+}
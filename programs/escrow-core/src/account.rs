@@ -0,0 +1,45 @@
+use core::ops::Deref;
+
+use crate::Error;
+
+/// 校验逻辑所需的最小账户接口，由 `adapters` 中的各版本实现
+pub trait AccountAccess {
+    /// 借用中的账户数据
+    type Data<'a>: Deref<Target = [u8]>
+    where
+        Self: 'a;
+
+    fn key(&self) -> &[u8; 32];
+
+    fn is_signer(&self) -> bool;
+
+    fn is_writable(&self) -> bool;
+
+    fn is_owned_by(&self, program: &[u8; 32]) -> bool;
+
+    fn lamports(&self) -> u64;
+
+    fn data_len(&self) -> usize;
+
+    /// 借用账户数据；已被可变借用时返回 `Error::AccountBorrowFailed`
+    fn try_data(&self) -> Result<Self::Data<'_>, Error>;
+
+    /// 同一版本 pinocchio 的 PDA 推导 (`seeds` 已含 bump)；不是合法 PDA 时返回 `Error::InvalidSeeds`
+    fn create_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> Result<[u8; 32], Error>;
+
+    /// 查找 PDA 与规范 bump
+    fn find_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> ([u8; 32], u8);
+}
+
+/// 可作为地址比较的类型 (`[u8; 32]`、0.10 的 `Address`)，供 `#[derive(Accounts)]` 的
+/// `owner = ...` / `address = ...` / `seeds::program = ...` 接受任一版本的常量
+pub trait AsKey {
+    fn as_key(&self) -> &[u8; 32];
+}
+
+impl AsKey for [u8; 32] {
+    #[inline(always)]
+    fn as_key(&self) -> &[u8; 32] {
+        self
+    }
+}
@@ -0,0 +1,7 @@
+//! 各版本 pinocchio 的账户类型适配
+
+#[cfg(feature = "pinocchio-09")]
+mod pinocchio_09;
+
+#[cfg(feature = "pinocchio-10")]
+mod pinocchio_10;
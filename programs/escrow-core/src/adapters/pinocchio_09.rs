@@ -0,0 +1,82 @@
+use pinocchio_09::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey,
+};
+
+use crate::{AccountAccess, Error, EscrowError};
+
+impl AccountAccess for AccountInfo {
+    type Data<'a> = Ref<'a, [u8]>;
+
+    #[inline(always)]
+    fn key(&self) -> &[u8; 32] {
+        AccountInfo::key(self)
+    }
+
+    #[inline(always)]
+    fn is_signer(&self) -> bool {
+        AccountInfo::is_signer(self)
+    }
+
+    #[inline(always)]
+    fn is_writable(&self) -> bool {
+        AccountInfo::is_writable(self)
+    }
+
+    #[inline(always)]
+    fn is_owned_by(&self, program: &[u8; 32]) -> bool {
+        AccountInfo::is_owned_by(self, program)
+    }
+
+    #[inline(always)]
+    fn lamports(&self) -> u64 {
+        AccountInfo::lamports(self)
+    }
+
+    #[inline(always)]
+    fn data_len(&self) -> usize {
+        AccountInfo::data_len(self)
+    }
+
+    #[inline(always)]
+    fn try_data(&self) -> Result<Self::Data<'_>, Error> {
+        self.try_borrow_data()
+            .map_err(|_| Error::AccountBorrowFailed)
+    }
+
+    #[inline(always)]
+    fn create_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> Result<[u8; 32], Error> {
+        pubkey::create_program_address(seeds, program).map_err(|_| Error::InvalidSeeds)
+    }
+
+    #[inline(always)]
+    fn find_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> ([u8; 32], u8) {
+        pubkey::find_program_address(seeds, program)
+    }
+}
+
+impl From<Error> for ProgramError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::InvalidAccountData => ProgramError::InvalidAccountData,
+            Error::InvalidAccountOwner => ProgramError::InvalidAccountOwner,
+            Error::InvalidArgument => ProgramError::InvalidArgument,
+            Error::InvalidInstructionData => ProgramError::InvalidInstructionData,
+            Error::InvalidSeeds => ProgramError::InvalidSeeds,
+            Error::Immutable => ProgramError::Immutable,
+            Error::MissingRequiredSignature => ProgramError::MissingRequiredSignature,
+            Error::NotEnoughAccountKeys => ProgramError::NotEnoughAccountKeys,
+            Error::AccountBorrowFailed => ProgramError::AccountBorrowFailed,
+            Error::ArithmeticOverflow => ProgramError::ArithmeticOverflow,
+            Error::Escrow(e) => e.into(),
+            Error::AccountFlag(e) => ProgramError::Custom(e.code()),
+        }
+    }
+}
+
+impl From<EscrowError> for ProgramError {
+    fn from(e: EscrowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
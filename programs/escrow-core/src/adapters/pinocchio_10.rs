@@ -0,0 +1,100 @@
+use pinocchio_10::{AccountView, Address, account::Ref, cpi::set_return_data, error::ProgramError};
+
+use crate::{AccountAccess, AsKey, Error, EscrowError, Key, SettledAmounts};
+
+impl AccountAccess for AccountView {
+    type Data<'a> = Ref<'a, [u8]>;
+
+    #[inline(always)]
+    fn key(&self) -> &[u8; 32] {
+        self.address().as_array()
+    }
+
+    #[inline(always)]
+    fn is_signer(&self) -> bool {
+        AccountView::is_signer(self)
+    }
+
+    #[inline(always)]
+    fn is_writable(&self) -> bool {
+        AccountView::is_writable(self)
+    }
+
+    #[inline(always)]
+    fn is_owned_by(&self, program: &[u8; 32]) -> bool {
+        // 与 `AccountView::owned_by` 相同，只是比较的是字节数组
+        unsafe { self.owner() }.as_array() == program
+    }
+
+    #[inline(always)]
+    fn lamports(&self) -> u64 {
+        AccountView::lamports(self)
+    }
+
+    #[inline(always)]
+    fn data_len(&self) -> usize {
+        AccountView::data_len(self)
+    }
+
+    #[inline(always)]
+    fn try_data(&self) -> Result<Self::Data<'_>, Error> {
+        self.try_borrow().map_err(|_| Error::AccountBorrowFailed)
+    }
+
+    #[inline(always)]
+    fn create_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> Result<[u8; 32], Error> {
+        Address::create_program_address(seeds, &Address::new_from_array(*program))
+            .map(|address| address.to_bytes())
+            .map_err(|_| Error::InvalidSeeds)
+    }
+
+    #[inline(always)]
+    fn find_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> ([u8; 32], u8) {
+        let (address, bump) =
+            Address::find_program_address(seeds, &Address::new_from_array(*program));
+        (address.to_bytes(), bump)
+    }
+}
+
+impl AsKey for Address {
+    #[inline(always)]
+    fn as_key(&self) -> &[u8; 32] {
+        self.as_array()
+    }
+}
+
+// `Address` 是 `#[repr(transparent)]` 的 `[u8; 32]`
+unsafe impl Key for Address {}
+
+impl SettledAmounts {
+    /// 作为本条指令的返回数据；任何 CPI 都会清空返回数据，须在最后一次 CPI 之后调用
+    #[inline(always)]
+    pub fn set_return_data(&self) {
+        set_return_data(&self.to_bytes());
+    }
+}
+
+impl From<Error> for ProgramError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::InvalidAccountData => ProgramError::InvalidAccountData,
+            Error::InvalidAccountOwner => ProgramError::InvalidAccountOwner,
+            Error::InvalidArgument => ProgramError::InvalidArgument,
+            Error::InvalidInstructionData => ProgramError::InvalidInstructionData,
+            Error::InvalidSeeds => ProgramError::InvalidSeeds,
+            Error::Immutable => ProgramError::Immutable,
+            Error::MissingRequiredSignature => ProgramError::MissingRequiredSignature,
+            Error::NotEnoughAccountKeys => ProgramError::NotEnoughAccountKeys,
+            Error::AccountBorrowFailed => ProgramError::AccountBorrowFailed,
+            Error::ArithmeticOverflow => ProgramError::ArithmeticOverflow,
+            Error::Escrow(e) => e.into(),
+            Error::AccountFlag(e) => ProgramError::Custom(e.code()),
+        }
+    }
+}
+
+impl From<EscrowError> for ProgramError {
+    fn from(e: EscrowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,126 @@
+/*
+Anchor 判别符：`sha256("<namespace>:<name>")` 的前 8 字节。
+
+指令用 `global:<snake_case 名>`，账户用 `account:<类型名>`。
+这里用 const fn 实现 SHA-256，判别符在编译期算出，不引入哈希库，也不占用链上 CU。
+ */
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// `sha256("<namespace>:<name>")[..8]`
+pub const fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let message = Message {
+        namespace: namespace.as_bytes(),
+        name: name.as_bytes(),
+    };
+    let mut state = H;
+
+    let mut block = 0;
+    while block < message.blocks() {
+        let mut w = [0u32; 64];
+        let mut i = 0;
+        while i < 16 {
+            let offset = block * 64 + i * 4;
+            w[i] = u32::from_be_bytes([
+                message.byte(offset),
+                message.byte(offset + 1),
+                message.byte(offset + 2),
+                message.byte(offset + 3),
+            ]);
+            i += 1;
+        }
+        while i < 64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+            i += 1;
+        }
+
+        let mut v = state;
+        i = 0;
+        while i < 64 {
+            let [a, b, c, d, e, f, g, h] = v;
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            v = [
+                t1.wrapping_add(s0.wrapping_add(maj)),
+                a,
+                b,
+                c,
+                d.wrapping_add(t1),
+                e,
+                f,
+                g,
+            ];
+            i += 1;
+        }
+
+        i = 0;
+        while i < 8 {
+            state[i] = state[i].wrapping_add(v[i]);
+            i += 1;
+        }
+        block += 1;
+    }
+
+    let [a, b, c, d] = state[0].to_be_bytes();
+    let [e, f, g, h] = state[1].to_be_bytes();
+    [a, b, c, d, e, f, g, h]
+}
+
+/// `namespace:name` 加上 SHA-256 的补位，按下标逐字节读取
+struct Message<'a> {
+    namespace: &'a [u8],
+    name: &'a [u8],
+}
+
+impl Message<'_> {
+    const fn len(&self) -> usize {
+        self.namespace.len() + 1 + self.name.len()
+    }
+
+    /// 补位后的块数：消息、0x80 与 8 字节的比特长度
+    const fn blocks(&self) -> usize {
+        (self.len() + 9).div_ceil(64)
+    }
+
+    const fn byte(&self, i: usize) -> u8 {
+        let (len, padded) = (self.len(), self.blocks() * 64);
+        if i < self.namespace.len() {
+            self.namespace[i]
+        } else if i == self.namespace.len() {
+            b':'
+        } else if i < len {
+            self.name[i - self.namespace.len() - 1]
+        } else if i == len {
+            0x80
+        } else if i >= padded - 8 {
+            ((len as u64 * 8) >> (8 * (padded - 1 - i))) as u8
+        } else {
+            0
+        }
+    }
+}
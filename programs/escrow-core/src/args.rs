@@ -0,0 +1,49 @@
+/*
+指令参数的版本化编码：版本由数据长度区分，不额外占用版本字节。
+
+每个新版本只在上一版末尾追加一组定长字段；旧客户端发送较短的数据，
+缺失的字段组取默认值，因此增加参数不会破坏已有的调用方。
+解析时只接受各版本的确切长度：字段组只给了一半、或多出无法识别的字节都返回
+`InvalidInstructionData`，新客户端误连旧程序时会明确失败，而不是参数被静默忽略。
+ */
+
+use crate::Error;
+
+pub struct ArgsReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ArgsReader<'a> {
+    #[inline(always)]
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// 必填字段组
+    #[inline(always)]
+    pub fn read<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let Some((field, rest)) = self.data.split_first_chunk::<N>() else {
+            return Err(Error::InvalidInstructionData);
+        };
+        self.data = rest;
+        Ok(*field)
+    }
+
+    /// 较新版本追加的字段组：数据已读完时为 `None` (旧客户端)
+    #[inline(always)]
+    pub fn optional<const N: usize>(&mut self) -> Result<Option<[u8; N]>, Error> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        self.read().map(Some)
+    }
+
+    /// 所有已知版本的字段都已读取，不允许多余字节
+    #[inline(always)]
+    pub fn finish(self) -> Result<(), Error> {
+        if !self.data.is_empty() {
+            return Err(Error::InvalidInstructionData);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+/// 声明 `EscrowError`：每个变体的错误码、文档和 IDL 中的英文说明写在一处，
+/// `ALL` / `name` / `msg` 都由这张表生成，IDL 的错误表不会漏掉或错配变体。
+macro_rules! escrow_errors {
+    ($($(#[doc = $doc:literal])* $name:ident = $code:literal => $msg:literal,)*) => {
+        /// 托管程序的自定义错误码 (以 `ProgramError::Custom` 返回)
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(u32)]
+        pub enum EscrowError {
+            $($(#[doc = $doc])* $name = $code,)*
+        }
+
+        impl EscrowError {
+            /// 全部变体，按声明顺序
+            pub const ALL: &'static [Self] = &[$(Self::$name,)*];
+
+            /// 变体名，即 IDL 中的 `name`
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$name => stringify!($name),)*
+                }
+            }
+
+            /// IDL 中的英文说明
+            pub const fn msg(self) -> &'static str {
+                match self {
+                    $(Self::$name => $msg,)*
+                }
+            }
+        }
+    };
+}
+
+escrow_errors! {
+    /// 推荐费率超过 10_000 bps
+    InvalidReferralBps = 0 => "Referral fee exceeds 10000 bps",
+    /// 指令已被管理员暂停
+    InstructionPaused = 1 => "Instruction is paused by the admin",
+    /// 暂停标志包含不可暂停的指令
+    InvalidPauseFlags = 2 => "Pause flags include a non-pausable instruction",
+    /// 签名者不是管理员 (或待接任的管理员)
+    UnauthorizedAdmin = 3 => "Signer is not the (pending) admin",
+    /// Mint 不在白名单中
+    MintNotAllowed = 4 => "Mint is not on the allow list",
+    /// Mint 在黑名单中
+    MintDenied = 5 => "Mint is on the deny list",
+    /// 未知的 Mint 策略状态
+    InvalidMintPolicy = 6 => "Unknown mint policy status",
+    /// 租金接收者与托管记录不一致
+    InvalidRentRecipient = 7 => "Rent recipient does not match the escrow",
+    /// Token B 收款人与托管记录不一致
+    InvalidProceedsRecipient = 8 => "Proceeds recipient does not match the escrow",
+    /// 报价已成交 (Claim 模式下等待创建者领取)
+    OfferAlreadyFilled = 9 => "Offer is already filled",
+    /// 报价尚未成交，无可领取的收益
+    OfferNotFilled = 10 => "Offer is not filled yet",
+    /// 未知的结算方式，或该报价不是 Claim 模式
+    InvalidSettlement = 11 => "Unknown settlement mode, or the offer is not in claim mode",
+    /// 签名者不是该报价的创建者
+    InvalidMaker = 12 => "Signer is not the maker of this offer",
+    /// 成交条款超出 taker 在 Take 参数中给出的限制
+    SlippageExceeded = 13 => "Fill is outside the taker's max_amount_in / min_amount_out limits",
+    /// 推荐人是报价的创建者、接受者或 Token B 收款人
+    InvalidReferrer = 14 => "Referrer is the maker, the taker or the proceeds recipient of this offer",
+}
+
+/// 共用逻辑返回的错误：内置 `ProgramError` 中用到的部分，加上自定义错误码。
+/// 各 adapter 把它一一映射到对应版本的 `ProgramError`。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidAccountData,
+    InvalidAccountOwner,
+    InvalidArgument,
+    InvalidInstructionData,
+    InvalidSeeds,
+    Immutable,
+    MissingRequiredSignature,
+    NotEnoughAccountKeys,
+    AccountBorrowFailed,
+    ArithmeticOverflow,
+    Escrow(EscrowError),
+    AccountFlag(AccountFlagError),
+}
+
+/// 某个固定账户位置缺少声明的签名 / 可写标志 (位置即 `ACCOUNTS` 中的下标)。
+/// 以 `Custom(0x100 | slot)` / `Custom(0x200 | slot)` 返回，不与 `EscrowError` 重叠，
+/// 客户端可据此还原出错的账户名。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountFlagError {
+    MissingSigner(u8),
+    NotWritable(u8),
+}
+
+impl AccountFlagError {
+    const MISSING_SIGNER: u32 = 0x100;
+    const NOT_WRITABLE: u32 = 0x200;
+
+    pub const fn code(self) -> u32 {
+        match self {
+            Self::MissingSigner(slot) => Self::MISSING_SIGNER | slot as u32,
+            Self::NotWritable(slot) => Self::NOT_WRITABLE | slot as u32,
+        }
+    }
+
+    pub const fn from_code(code: u32) -> Option<Self> {
+        let slot = (code & 0xff) as u8;
+        match code & !0xff {
+            Self::MISSING_SIGNER => Some(Self::MissingSigner(slot)),
+            Self::NOT_WRITABLE => Some(Self::NotWritable(slot)),
+            _ => None,
+        }
+    }
+
+    /// 出错账户在指令账户列表中的下标
+    pub const fn slot(self) -> usize {
+        match self {
+            Self::MissingSigner(slot) | Self::NotWritable(slot) => slot as usize,
+        }
+    }
+}
+
+impl From<AccountFlagError> for Error {
+    fn from(e: AccountFlagError) -> Self {
+        Error::AccountFlag(e)
+    }
+}
+
+impl From<EscrowError> for Error {
+    fn from(e: EscrowError) -> Self {
+        Error::Escrow(e)
+    }
+}
@@ -0,0 +1,410 @@
+/*
+托管记录的两种布局，两代程序共用：
+
+- `Escrow`：当前布局，带推荐费、租金接收者、收益接收者与结算方式 (新版程序)；
+- `LegacyEscrow`：引入这些字段之前的 113 字节布局 (旧版程序，以及新版程序的旧报价)。
+
+地址字段的类型 `K` 由程序选择 (0.9 的 `Pubkey`，或 0.10 的 `Address`)，字节排布完全相同。
+`*_of` 系列直接从账户读取 PDA 种子与 mint，供 `#[account(...)]` 约束使用，两种布局都接受。
+ */
+
+use core::mem::size_of;
+
+use crate::{AccountAccess, AsKey, Error, Layout, load, load_mut, referral_fee};
+
+/// 托管记录中的地址类型
+///
+/// # Safety
+///
+/// 实现者必须是 `#[repr(transparent)]` 的 `[u8; 32]` (按 1 字节对齐、对任意字节有效)。
+pub unsafe trait Key: AsKey + Copy {}
+
+unsafe impl Key for [u8; 32] {}
+
+#[repr(C)]
+pub struct Escrow<K: Key = [u8; 32]> {
+    #[cfg(feature = "anchor")]
+    pub discriminator: [u8; 8], // Anchor account discriminator (see `ESCROW_DISCRIMINATOR`)
+    pub seed: [u8; 8],         // Random seed for PDA derivation (u64 LE)
+    pub maker: K,              // Creator of the escrow
+    pub mint_a: K,             // Token being deposited
+    pub mint_b: K,             // Token being requested
+    pub receive: [u8; 8],      // Amount of token B wanted (u64 LE)
+    pub bump: [u8; 1],         // PDA bump seed
+    pub referral_bps: [u8; 2], // Share of token B paid to the referrer (u16 LE, bps)
+    pub rent_recipient: K,     // Receives the escrow and vault rent on close
+    pub proceeds_recipient: K, // Owner of the token B account paid on take
+    pub settlement: u8,        // How token B reaches the maker (push or claim)
+    pub status: u8,            // Open, or filled and waiting for a claim
+}
+
+unsafe impl<K: Key> Layout for Escrow<K> {
+    const LEN: usize = Self::LEN;
+}
+
+/// `Escrow` 的 Anchor 账户判别符：`sha256("account:Escrow")[..8]`
+pub const ESCROW_DISCRIMINATOR: [u8; 8] = crate::anchor::discriminator("account", "Escrow");
+
+impl<K: Key> Escrow<K> {
+    /// 账户开头的判别符：`anchor` feature 下为 Anchor 账户判别符，否则为空
+    #[cfg(feature = "anchor")]
+    pub const DISCRIMINATOR: &'static [u8] = &ESCROW_DISCRIMINATOR;
+    #[cfg(not(feature = "anchor"))]
+    pub const DISCRIMINATOR: &'static [u8] = &[];
+
+    pub const LEN: usize = Self::DISCRIMINATOR.len()
+        + size_of::<[u8; 8]>()
+        + size_of::<K>()
+        + size_of::<K>()
+        + size_of::<K>()
+        + size_of::<[u8; 8]>()
+        + size_of::<[u8; 1]>()
+        + size_of::<[u8; 2]>()
+        + size_of::<K>()
+        + size_of::<K>()
+        + size_of::<u8>()
+        + size_of::<u8>();
+
+    // Token B 直接推送到收款人 ATA，或先存入收益金库等待创建者 Claim
+    pub const SETTLE_PUSH: u8 = 0;
+    pub const SETTLE_CLAIM: u8 = 1;
+
+    pub const STATUS_OPEN: u8 = 0;
+    pub const STATUS_FILLED: u8 = 1;
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, Error> {
+        load_mut(bytes)
+    }
+
+    /// 同时检查账户判别符 (`load_mut` 用于初始化，不检查)
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, Error> {
+        if !bytes.starts_with(Self::DISCRIMINATOR) {
+            return Err(Error::InvalidAccountData);
+        }
+        load(bytes)
+    }
+
+    /// 直接从托管账户读取 PDA 种子中的 `seed` (小端)，供 `#[account(seeds = ...)]` 使用。
+    /// 旧版布局同样接受，使 Take / Refund 能校验旧报价的 PDA。
+    #[inline(always)]
+    pub fn seed_bytes_of<A: AccountAccess>(account: &A) -> Result<[u8; 8], Error> {
+        EscrowVersion::<K>::read(account, |escrow| *escrow.seed())
+    }
+
+    /// 直接从托管账户读取 PDA 的 bump (同样接受旧版布局)
+    #[inline(always)]
+    pub fn bump_of<A: AccountAccess>(account: &A) -> Result<u8, Error> {
+        EscrowVersion::<K>::read(account, |escrow| escrow.bump()[0])
+    }
+
+    /// 直接从托管账户读取 `mint_a`，供 `#[account(address = ...)]` 使用 (同样接受旧版布局)
+    #[inline(always)]
+    pub fn mint_a_of<A: AccountAccess>(account: &A) -> Result<K, Error> {
+        EscrowVersion::<K>::read(account, |escrow| *escrow.mint_a())
+    }
+
+    /// 直接从托管账户读取 `mint_b` (同样接受旧版布局)
+    #[inline(always)]
+    pub fn mint_b_of<A: AccountAccess>(account: &A) -> Result<K, Error> {
+        EscrowVersion::<K>::read(account, |escrow| *escrow.mint_b())
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    #[inline(always)]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed.to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_maker(&mut self, maker: K) {
+        self.maker = maker;
+    }
+
+    #[inline(always)]
+    pub fn set_mint_a(&mut self, mint_a: K) {
+        self.mint_a = mint_a;
+    }
+
+    #[inline(always)]
+    pub fn set_mint_b(&mut self, mint_b: K) {
+        self.mint_b = mint_b;
+    }
+
+    #[inline(always)]
+    pub fn receive(&self) -> u64 {
+        u64::from_le_bytes(self.receive)
+    }
+
+    #[inline(always)]
+    pub fn set_receive(&mut self, receive: u64) {
+        self.receive = receive.to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_bump(&mut self, bump: [u8; 1]) {
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn referral_bps(&self) -> u16 {
+        u16::from_le_bytes(self.referral_bps)
+    }
+
+    #[inline(always)]
+    pub fn set_referral_bps(&mut self, referral_bps: u16) {
+        self.referral_bps = referral_bps.to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_rent_recipient(&mut self, rent_recipient: K) {
+        self.rent_recipient = rent_recipient;
+    }
+
+    #[inline(always)]
+    pub fn set_proceeds_recipient(&mut self, proceeds_recipient: K) {
+        self.proceeds_recipient = proceeds_recipient;
+    }
+
+    #[inline(always)]
+    pub fn set_settlement(&mut self, settlement: u8) {
+        self.settlement = settlement;
+    }
+
+    #[inline(always)]
+    pub fn set_status(&mut self, status: u8) {
+        self.status = status;
+    }
+
+    #[inline(always)]
+    pub fn is_claim_mode(&self) -> bool {
+        self.settlement == Self::SETTLE_CLAIM
+    }
+
+    #[inline(always)]
+    pub fn is_filled(&self) -> bool {
+        self.status == Self::STATUS_FILLED
+    }
+
+    /// 带推荐人成交时从 `receive` 中分给推荐人的推荐费 (向下取整)
+    #[inline(always)]
+    pub fn referral_fee(&self) -> u64 {
+        referral_fee(self.receive(), self.referral_bps())
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        maker: K,
+        mint_a: K,
+        mint_b: K,
+        receive: u64,
+        bump: [u8; 1],
+    ) {
+        #[cfg(feature = "anchor")]
+        {
+            self.discriminator = ESCROW_DISCRIMINATOR;
+        }
+        self.seed = seed.to_le_bytes();
+        self.maker = maker;
+        self.mint_a = mint_a;
+        self.mint_b = mint_b;
+        self.receive = receive.to_le_bytes();
+        self.bump = bump;
+    }
+}
+
+// --- 旧版托管布局 (113 字节) ---
+// 旧版程序 `solana_pinocchio_escrow` 只写这一布局。新版程序以同一程序 ID
+// 读到它时按没有推荐费、创建者同时是租金接收者与收款人、推送结算的报价处理，
+// 仍可 Take 或 Refund。
+#[repr(C)]
+pub struct LegacyEscrow<K: Key = [u8; 32]> {
+    pub seed: [u8; 8],    // Random seed for PDA derivation (u64 LE)
+    pub maker: K,         // Creator of the escrow
+    pub mint_a: K,        // Token being deposited
+    pub mint_b: K,        // Token being requested
+    pub receive: [u8; 8], // Amount of token B wanted (u64 LE)
+    pub bump: [u8; 1],    // PDA bump seed
+}
+
+unsafe impl<K: Key> Layout for LegacyEscrow<K> {
+    const LEN: usize = Self::LEN;
+}
+
+impl<K: Key> LegacyEscrow<K> {
+    pub const LEN: usize = size_of::<[u8; 8]>()
+        + size_of::<K>()
+        + size_of::<K>()
+        + size_of::<K>()
+        + size_of::<[u8; 8]>()
+        + size_of::<[u8; 1]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, Error> {
+        load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, Error> {
+        load(bytes)
+    }
+
+    /// 直接从托管账户读取 PDA 种子中的 `seed`，只接受旧版布局 (旧版程序的约束使用)
+    #[inline(always)]
+    pub fn seed_bytes_of<A: AccountAccess>(account: &A) -> Result<[u8; 8], Error> {
+        Ok(Self::load(&account.try_data()?)?.seed)
+    }
+
+    #[inline(always)]
+    pub fn bump_of<A: AccountAccess>(account: &A) -> Result<u8, Error> {
+        Ok(Self::load(&account.try_data()?)?.bump[0])
+    }
+
+    #[inline(always)]
+    pub fn mint_a_of<A: AccountAccess>(account: &A) -> Result<K, Error> {
+        Ok(Self::load(&account.try_data()?)?.mint_a)
+    }
+
+    #[inline(always)]
+    pub fn mint_b_of<A: AccountAccess>(account: &A) -> Result<K, Error> {
+        Ok(Self::load(&account.try_data()?)?.mint_b)
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    #[inline(always)]
+    pub fn receive(&self) -> u64 {
+        u64::from_le_bytes(self.receive)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        maker: K,
+        mint_a: K,
+        mint_b: K,
+        receive: u64,
+        bump: [u8; 1],
+    ) {
+        self.seed = seed.to_le_bytes();
+        self.maker = maker;
+        self.mint_a = mint_a;
+        self.mint_b = mint_b;
+        self.receive = receive.to_le_bytes();
+        self.bump = bump;
+    }
+}
+
+/// 按账户长度区分的托管记录：当前布局，或旧版布局
+pub enum EscrowVersion<'a, K: Key = [u8; 32]> {
+    Current(&'a Escrow<K>),
+    Legacy(&'a LegacyEscrow<K>),
+}
+
+impl<'a, K: Key> EscrowVersion<'a, K> {
+    #[inline(always)]
+    pub fn load(bytes: &'a [u8]) -> Result<Self, Error> {
+        match bytes.len() {
+            len if len == LegacyEscrow::<K>::LEN => Ok(Self::Legacy(LegacyEscrow::load(bytes)?)),
+            _ => Ok(Self::Current(Escrow::load(bytes)?)),
+        }
+    }
+
+    /// 借用账户数据，按版本解码后交给 `f`
+    #[inline(always)]
+    fn read<A: AccountAccess, T>(
+        account: &A,
+        f: impl FnOnce(&EscrowVersion<K>) -> T,
+    ) -> Result<T, Error> {
+        let data = account.try_data()?;
+        Ok(f(&EscrowVersion::load(&data)?))
+    }
+
+    /// PDA 种子中的 `seed` (小端字节)
+    #[inline(always)]
+    pub fn seed(&self) -> &'a [u8; 8] {
+        match self {
+            Self::Current(escrow) => &escrow.seed,
+            Self::Legacy(escrow) => &escrow.seed,
+        }
+    }
+
+    #[inline(always)]
+    pub fn bump(&self) -> &'a [u8; 1] {
+        match self {
+            Self::Current(escrow) => &escrow.bump,
+            Self::Legacy(escrow) => &escrow.bump,
+        }
+    }
+
+    #[inline(always)]
+    pub fn mint_a(&self) -> &'a K {
+        match self {
+            Self::Current(escrow) => &escrow.mint_a,
+            Self::Legacy(escrow) => &escrow.mint_a,
+        }
+    }
+
+    #[inline(always)]
+    pub fn mint_b(&self) -> &'a K {
+        match self {
+            Self::Current(escrow) => &escrow.mint_b,
+            Self::Legacy(escrow) => &escrow.mint_b,
+        }
+    }
+
+    #[inline(always)]
+    pub fn receive(&self) -> u64 {
+        match self {
+            Self::Current(escrow) => escrow.receive(),
+            Self::Legacy(escrow) => escrow.receive(),
+        }
+    }
+
+    /// 旧版托管没有推荐费
+    #[inline(always)]
+    pub fn referral_bps(&self) -> u16 {
+        match self {
+            Self::Current(escrow) => escrow.referral_bps(),
+            Self::Legacy(_) => 0,
+        }
+    }
+
+    /// 旧版托管没有租金接收者字段，租金一律退回创建者
+    #[inline(always)]
+    pub fn rent_recipient(&self) -> &'a K {
+        match self {
+            Self::Current(escrow) => &escrow.rent_recipient,
+            Self::Legacy(escrow) => &escrow.maker,
+        }
+    }
+
+    #[inline(always)]
+    pub fn proceeds_recipient(&self) -> &'a K {
+        match self {
+            Self::Current(escrow) => &escrow.proceeds_recipient,
+            Self::Legacy(escrow) => &escrow.maker,
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_claim_mode(&self) -> bool {
+        matches!(self, Self::Current(escrow) if escrow.is_claim_mode())
+    }
+
+    #[inline(always)]
+    pub fn is_filled(&self) -> bool {
+        matches!(self, Self::Current(escrow) if escrow.is_filled())
+    }
+}
@@ -0,0 +1,44 @@
+use core::mem::{align_of, size_of};
+
+use crate::Error;
+
+/// 定长、按 `#[repr(C)]` 排布的账户数据，可以零拷贝地从字节中读取。
+///
+/// 多字节整数以小端字节数组 (`[u8; N]`) 存放，由访问器转换，
+/// 这样结构体按 1 字节对齐、没有填充，可以指向账户数据中的任意位置。
+///
+/// # Safety
+///
+/// 实现者只能包含对任意字节都有效、按 1 字节对齐的字段 (`u8`、字节数组及由它们组成的类型)，
+/// 且 `LEN` 必须等于 `size_of::<Self>()`。`load` / `load_mut` 在编译期检查后两条。
+pub unsafe trait Layout: Sized {
+    const LEN: usize;
+}
+
+/// 没有填充且按 1 字节对齐，否则不能把任意偏移的字节切片当作 `T` 读取
+const fn assert_layout<T: Layout>() {
+    assert!(
+        T::LEN == size_of::<T>(),
+        "Layout::LEN must equal size_of::<T>()"
+    );
+    assert!(align_of::<T>() == 1, "Layout types must be 1-byte aligned");
+}
+
+/// 长度与 `T::LEN` 不符时返回 `Error::InvalidAccountData`
+#[inline(always)]
+pub fn load<T: Layout>(bytes: &[u8]) -> Result<&T, Error> {
+    const { assert_layout::<T>() };
+    if bytes.len() != T::LEN {
+        return Err(Error::InvalidAccountData);
+    }
+    Ok(unsafe { &*(bytes.as_ptr() as *const T) })
+}
+
+#[inline(always)]
+pub fn load_mut<T: Layout>(bytes: &mut [u8]) -> Result<&mut T, Error> {
+    const { assert_layout::<T>() };
+    if bytes.len() != T::LEN {
+        return Err(Error::InvalidAccountData);
+    }
+    Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut T) })
+}
@@ -0,0 +1,60 @@
+/*
+两代托管程序共用的底层工具：账户校验、指令参数与尾部账户解析、零拷贝布局加载、
+`Escrow` / `LegacyEscrow` 布局、`Quote` 定价与结算结果、关闭账户的算术，以及错误码。
+两个程序的账户结构体都用 `escrow-derive` 的 `#[derive(Accounts)]` 声明约束，展开为 `validate` 中的检查；
+各程序只保留 Make / Take / Refund 的流程与 CPI。
+
+这里不依赖任何版本的 pinocchio，账户通过 `AccountAccess` 访问；
+`pinocchio-09` / `pinocchio-10` feature 分别为 `AccountInfo` 与 `AccountView` 实现该 trait，
+并提供 `Error` 到对应 `ProgramError` 的转换，程序中可直接用 `?` 传播。
+ */
+
+#![no_std]
+
+pub mod account;
+pub use account::*;
+
+pub mod adapters;
+
+pub mod anchor;
+
+pub mod args;
+pub use args::*;
+
+pub mod error;
+pub use error::*;
+
+pub mod escrow;
+pub use escrow::*;
+
+pub mod layout;
+pub use layout::*;
+
+pub mod pricing;
+pub use pricing::*;
+
+pub mod remaining;
+pub use remaining::*;
+
+pub mod spec;
+pub use spec::*;
+
+pub mod validate;
+
+// --- 托管 PDA 种子 ---
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+// --- 费率基数 (10_000 bps = 100%) ---
+pub const MAX_BPS: u16 = 10_000;
+
+/// SPL Token 程序 (TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
+pub const TOKEN_PROGRAM_ID: [u8; 32] = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xd7, 0x65, 0xa1, 0x93, 0xd9, 0xcb, 0xe1, 0x46, 0xce, 0xeb, 0x79, 0xac,
+    0x1c, 0xb4, 0x85, 0xed, 0x5f, 0x5b, 0x37, 0x91, 0x3a, 0x8c, 0xf5, 0x85, 0x7e, 0xff, 0x00, 0xa9,
+];
+
+/// 关联代币账户程序 (ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL)
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: [u8; 32] = [
+    0x8c, 0x97, 0x25, 0x8f, 0x4e, 0x24, 0x89, 0xf1, 0xbb, 0x3d, 0x10, 0x29, 0x14, 0x8e, 0x0d, 0x83,
+    0x0b, 0x5a, 0x13, 0x99, 0xda, 0xff, 0x10, 0x84, 0x04, 0x8e, 0x7b, 0xd8, 0xdb, 0xe9, 0xf8, 0x59,
+];
@@ -0,0 +1,156 @@
+/*
+成交定价与结算结果：两代程序的 Take 以及新版的 Quote 指令共用同一份计算，不会出现偏差。
+
+当前报价只能整单成交：金库中的全部 Token A 换取 `receive` 数量的 Token B，接受者支付的总额
+始终是 `receive`。若传入推荐人账户，其中按 `referral_bps` 计算的一部分付给推荐人，其余付给收款人。
+
+计算部分只依赖数值，不读取账户，新版程序 `proofs.rs` 中的 Kani 证明直接针对这些函数。
+ */
+
+use core::mem::size_of;
+
+use crate::{Error, EscrowVersion, Key, MAX_BPS};
+
+/// 推荐费：按 `receive` 的 `referral_bps` 计算的 Token B 数量 (向下取整)，从 `receive` 中分出。
+/// `referral_bps` 不超过 `MAX_BPS` 时不会大于 `receive`
+#[inline(always)]
+pub fn referral_fee(receive: u64, referral_bps: u16) -> u64 {
+    (receive as u128 * referral_bps as u128 / MAX_BPS as u128) as u64
+}
+
+/// 关闭账户后接收者的余额；溢出时报错而不是回绕，保证 lamports 总量不变
+#[inline(always)]
+pub fn close_lamports(lamports: u64, destination: u64) -> Result<u64, Error> {
+    destination
+        .checked_add(lamports)
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// 报价只能整单成交且没有有效期：`amount_out` 总是金库的全部余额，
+/// 未成交前 `fillable` 一直为真，因此不返回剩余数量或过期状态
+pub struct Quote {
+    pub amount_out: u64, // Token A the taker receives
+    pub amount_in: u64,  // Token B the taker pays, always `receive`
+    pub fee: u64,        // Part of `amount_in` paid to the referrer
+    pub fillable: bool,  // False once the offer has been filled
+}
+
+impl Quote {
+    pub const LEN: usize = size_of::<u64>() * 3 + size_of::<u8>();
+
+    /// `vault_amount` 为金库当前余额；已成交的报价金库已关闭，传 0 即可
+    #[inline(always)]
+    pub fn new<K: Key>(escrow: &EscrowVersion<K>, vault_amount: u64, with_referrer: bool) -> Self {
+        Self::price(
+            escrow.receive(),
+            escrow.referral_bps(),
+            escrow.is_filled(),
+            vault_amount,
+            with_referrer,
+        )
+    }
+
+    /// 与 `new` 相同，参数为托管中参与定价的字段 (旧版布局没有推荐费，为 0)
+    #[inline(always)]
+    pub fn price(
+        receive: u64,
+        referral_bps: u16,
+        filled: bool,
+        vault_amount: u64,
+        with_referrer: bool,
+    ) -> Self {
+        if filled {
+            return Self {
+                amount_out: 0,
+                amount_in: 0,
+                fee: 0,
+                fillable: false,
+            };
+        }
+
+        let fee = if with_referrer {
+            referral_fee(receive, referral_bps)
+        } else {
+            0
+        };
+        Self {
+            amount_out: vault_amount,
+            amount_in: receive,
+            fee,
+            fillable: true,
+        }
+    }
+
+    /// 收款人实际得到的 Token B：`receive` 扣除推荐费
+    #[inline(always)]
+    pub fn maker_amount(&self) -> u64 {
+        self.amount_in - self.fee
+    }
+
+    #[inline(always)]
+    pub fn settled(&self) -> SettledAmounts {
+        SettledAmounts {
+            amount_out: self.amount_out,
+            amount_in: self.amount_in,
+            fee: self.fee,
+        }
+    }
+
+    /// 布局：`amount_out | amount_in | fee` (小端 u64) + `fillable` (u8)，共 25 字节
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[..SettledAmounts::LEN].copy_from_slice(&self.settled().to_bytes());
+        bytes[SettledAmounts::LEN] = self.fillable as u8;
+        bytes
+    }
+
+    /// 解码 Quote 指令的返回数据
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::LEN {
+            return Err(Error::InvalidAccountData);
+        }
+        let settled = SettledAmounts::from_bytes(&bytes[..SettledAmounts::LEN])?;
+
+        Ok(Self {
+            amount_out: settled.amount_out,
+            amount_in: settled.amount_in,
+            fee: settled.fee,
+            fillable: bytes[SettledAmounts::LEN] != 0,
+        })
+    }
+}
+
+/// Take / Refund 通过 `set_return_data` 返回的结算结果，
+/// CPI 调用方 (如串联多个托管的路由程序) 无需重新读取代币账户即可得知实际成交数量。
+///
+/// 布局固定为 3 个小端 u64 (共 24 字节)：`amount_out | amount_in | fee`
+pub struct SettledAmounts {
+    pub amount_out: u64, // Token A released from the vault
+    pub amount_in: u64,  // Token B paid by the taker, fee included
+    pub fee: u64,        // Part of `amount_in` paid to the referrer
+}
+
+impl SettledAmounts {
+    pub const LEN: usize = size_of::<u64>() * 3;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0..8].copy_from_slice(&self.amount_out.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.amount_in.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.fee.to_le_bytes());
+        bytes
+    }
+
+    /// 解码 `get_return_data` 取回的字节
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::LEN {
+            return Err(Error::InvalidAccountData);
+        }
+
+        Ok(Self {
+            amount_out: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            amount_in: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            fee: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+/*
+固定账户之后的尾部账户：按顺序逐个取出必需 / 可选账户，其余部分原样留给调用方。
+
+可选账户沿用 Anchor 的约定：在该位置传入本程序 ID 表示"未提供"，
+这样后面的可选账户不会因为前面某个缺省而错位；列表提前结束同样视为未提供。
+新增的可选账户只能追加在末尾，旧客户端不传即可，不会破坏已有布局。
+ */
+
+use crate::{AccountAccess, Error};
+
+pub struct RemainingAccounts<'a, A> {
+    accounts: &'a [A],
+    program: &'a [u8; 32],
+}
+
+impl<'a, A: AccountAccess> RemainingAccounts<'a, A> {
+    /// `program` 为 "未提供" 哨兵，即本程序 ID
+    #[inline(always)]
+    pub fn new(accounts: &'a [A], program: &'a [u8; 32]) -> Self {
+        Self { accounts, program }
+    }
+
+    /// 下一个必需账户；不识别哨兵 (如 cpi-events 的 program 账户本身就是程序 ID)
+    #[inline(always)]
+    pub fn required(&mut self) -> Result<&'a A, Error> {
+        let [account, rest @ ..] = self.accounts else {
+            return Err(Error::NotEnoughAccountKeys);
+        };
+        self.accounts = rest;
+        Ok(account)
+    }
+
+    /// 下一个可选账户；列表已用完或传入哨兵时为 `None`
+    #[inline(always)]
+    pub fn optional(&mut self) -> Option<&'a A> {
+        let [account, rest @ ..] = self.accounts else {
+            return None;
+        };
+        self.accounts = rest;
+        (account.key() != self.program).then_some(account)
+    }
+
+    /// 尚未取出的账户
+    #[inline(always)]
+    pub fn rest(self) -> &'a [A] {
+        self.accounts
+    }
+}
@@ -0,0 +1,68 @@
+/// 每条指令固定账户的声明 (顺序 + 签名 / 可写标志)。
+/// 处理器的 `validate::account_flags`、CPI 构造器与链下客户端的 `AccountMeta` 共用同一份。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountSpec {
+    pub name: &'static str,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+impl AccountSpec {
+    pub const fn readonly(name: &'static str) -> Self {
+        Self {
+            name,
+            signer: false,
+            writable: false,
+        }
+    }
+
+    pub const fn writable(name: &'static str) -> Self {
+        Self {
+            name,
+            signer: false,
+            writable: true,
+        }
+    }
+
+    pub const fn readonly_signer(name: &'static str) -> Self {
+        Self {
+            name,
+            signer: true,
+            writable: false,
+        }
+    }
+
+    pub const fn writable_signer(name: &'static str) -> Self {
+        Self {
+            name,
+            signer: true,
+            writable: true,
+        }
+    }
+
+    /// `name` 在 `accounts` 中的位置；在 const 中使用时，找不到即编译失败
+    pub const fn index_of(accounts: &[AccountSpec], name: &str) -> usize {
+        let mut index = 0;
+        while index < accounts.len() {
+            if eq(accounts[index].name.as_bytes(), name.as_bytes()) {
+                return index;
+            }
+            index += 1;
+        }
+        panic!("no account with this name");
+    }
+}
+
+const fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -0,0 +1,165 @@
+/*
+两代程序共用的账户校验。行为与原先各自的 `SignerAccount` / `ProgramAccount` /
+`MintInterface` / `AssociatedTokenAccount` 助手一致，代币账户的检查照搬 `pinocchio-token`
+的 `TokenAccount::from_account_*` (长度、所有者)，再比较 mint 与 owner。
+ */
+
+use crate::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, AccountAccess, AccountFlagError, AccountSpec, Error,
+    TOKEN_PROGRAM_ID,
+};
+
+// --- SPL Token 账户布局 ---
+pub const MINT_LEN: usize = 82;
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+#[inline(always)]
+pub fn signer(account: &impl AccountAccess) -> Result<(), Error> {
+    if !account.is_signer() {
+        return Err(Error::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+pub fn writable(account: &impl AccountAccess) -> Result<(), Error> {
+    if !account.is_writable() {
+        return Err(Error::Immutable);
+    }
+    Ok(())
+}
+
+/// 在任何 CPI 之前按 `specs` 逐个位置检查签名 / 可写标志，出错时指明位置
+#[inline(always)]
+pub fn account_flags<A: AccountAccess>(accounts: &[A], specs: &[AccountSpec]) -> Result<(), Error> {
+    if accounts.len() < specs.len() {
+        return Err(Error::NotEnoughAccountKeys);
+    }
+    for (slot, (account, spec)) in accounts.iter().zip(specs).enumerate() {
+        if spec.signer && !account.is_signer() {
+            return Err(AccountFlagError::MissingSigner(slot as u8).into());
+        }
+        if spec.writable && !account.is_writable() {
+            return Err(AccountFlagError::NotWritable(slot as u8).into());
+        }
+    }
+    Ok(())
+}
+
+/// 账户地址必须为 `expected`
+#[inline(always)]
+pub fn address(account: &impl AccountAccess, expected: &[u8; 32]) -> Result<(), Error> {
+    if account.key() != expected {
+        return Err(Error::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// 账户地址必须是 `seeds` (已含 bump) 在 `program` 下推导出的 PDA
+#[inline(always)]
+pub fn program_address<A: AccountAccess>(
+    account: &A,
+    seeds: &[&[u8]],
+    program: &[u8; 32],
+) -> Result<(), Error> {
+    if &A::create_program_address(seeds, program)? != account.key() {
+        return Err(Error::InvalidSeeds);
+    }
+    Ok(())
+}
+
+/// 未给出 bump 时查找规范 bump 再比较，返回该 bump
+#[inline(always)]
+pub fn find_program_address<A: AccountAccess>(
+    account: &A,
+    seeds: &[&[u8]],
+    program: &[u8; 32],
+) -> Result<u8, Error> {
+    let (address, bump) = A::find_program_address(seeds, program);
+    if &address != account.key() {
+        return Err(Error::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+/// 账户必须由 `program` 拥有
+#[inline(always)]
+pub fn owned_by(account: &impl AccountAccess, program: &[u8; 32]) -> Result<(), Error> {
+    if !account.is_owned_by(program) {
+        return Err(Error::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+/// SPL Token Mint 固定长度为 82
+#[inline(always)]
+pub fn mint(account: &impl AccountAccess) -> Result<(), Error> {
+    if account.data_len() != MINT_LEN {
+        return Err(Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// 代币账户属于 `owner` 且 mint 为 `mint`
+#[inline(always)]
+pub fn token_account(
+    account: &impl AccountAccess,
+    owner: &[u8; 32],
+    mint: &[u8; 32],
+) -> Result<(), Error> {
+    let data = token_account_data(account)?;
+    if &data[32..64] != owner || &data[0..32] != mint {
+        return Err(Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// `owner` 的 `mint` 关联代币账户：地址为 ATA 程序推导出的 PDA，且内容与之相符
+#[inline(always)]
+pub fn associated_token_account<A: AccountAccess>(
+    account: &A,
+    owner: &[u8; 32],
+    mint: &[u8; 32],
+) -> Result<(), Error> {
+    let (address, _) = A::find_program_address(
+        &[owner, &TOKEN_PROGRAM_ID, mint],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    );
+    if &address != account.key() {
+        return Err(Error::InvalidSeeds);
+    }
+    token_account(account, owner, mint)
+}
+
+/// 只校验代币账户的 mint，不限定所有者 (如推荐人的收款账户)
+#[inline(always)]
+pub fn token_account_mint(account: &impl AccountAccess, mint: &[u8; 32]) -> Result<(), Error> {
+    if &token_account_data(account)?[0..32] != mint {
+        return Err(Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// 只校验代币账户的所有者
+#[inline(always)]
+pub fn token_account_owner(account: &impl AccountAccess, owner: &[u8; 32]) -> Result<(), Error> {
+    if &token_account_data(account)?[32..64] != owner {
+        return Err(Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// 代币账户余额
+#[inline(always)]
+pub fn token_amount(account: &impl AccountAccess) -> Result<u64, Error> {
+    let data = token_account_data(account)?;
+    Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+#[inline(always)]
+fn token_account_data<A: AccountAccess>(account: &A) -> Result<A::Data<'_>, Error> {
+    if account.data_len() != TOKEN_ACCOUNT_LEN || !account.is_owned_by(&TOKEN_PROGRAM_ID) {
+        return Err(Error::InvalidAccountData);
+    }
+    account.try_data()
+}
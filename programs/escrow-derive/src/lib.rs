@@ -0,0 +1,333 @@
+/*
+`#[derive(Accounts)]`：把账户结构体上的 `#[account(...)]` 约束展开为 `escrow_core::validate` 调用。
+
+```ignore
+#[derive(Accounts)]
+pub struct RefundAccounts<'a> {
+    #[account(signer, writable)]
+    pub maker: &'a AccountView,
+    #[account(writable, owner = crate::ID)]
+    pub escrow: &'a AccountView,
+    #[account(writable, token::mint = mint_a, token::authority = escrow)]
+    pub vault: &'a AccountView,
+    ...
+}
+```
+
+生成：
+- `ACCOUNTS: [escrow_core::AccountSpec; N]`：字段名与 `signer` / `writable` 标志，供客户端构造 `AccountMeta`；
+- `try_accounts(accounts) -> Result<(Self, remaining), escrow_core::Error>`：
+  先按 `ACCOUNTS` 检查全部位置的标志 (出错时指明位置)，再用切片模式绑定账户，
+  逐个字段执行其余约束，多出的账户原样返回给调用方解析。
+  全部展开为内联的比较，不引入额外的间接调用。
+
+支持的约束 (同一字段内按下列顺序检查)：
+- `signer` / `writable`：签名与可写标志 (见上)；
+- `owner = EXPR` / `address = EXPR`：`EXPR` 可以是 `[u8; 32]` 或 0.10 的 `Address`；
+- `mint`：SPL Token Mint；
+- `seeds = [..]`，可选 `bump = EXPR` 与 `seeds::program = EXPR` (缺省 `crate::ID`)：
+  给出 bump 时直接推导比较，否则查找规范 bump；
+- `token::mint = FIELD` / `token::authority = FIELD`：SPL Token 账户的 mint 与所有者；
+- `associated_token::mint = FIELD, associated_token::authority = FIELD`：两者须同时给出，
+  除 mint 与所有者外还要求地址就是该所有者的 ATA。
+
+约束中的表达式可以引用其他字段 (已绑定为同名局部变量)，也可以用 `?` 传播 `escrow_core::Error`。
+ */
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, quote};
+use syn::{
+    Data, DeriveInput, Error, Expr, Fields, Lifetime, Path, Result, Token, Type,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+#[proc_macro_derive(Accounts, attributes(account))]
+pub fn derive_accounts(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input,
+            "#[derive(Accounts)] only supports structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &input,
+            "#[derive(Accounts)] requires named fields",
+        ));
+    };
+
+    let mut idents = Vec::new();
+    let mut specs = Vec::new();
+    let mut checks = Vec::new();
+    let mut account: Option<(Lifetime, Type)> = None;
+
+    for field in &fields.named {
+        let ident = field.ident.clone().unwrap();
+
+        // 每个字段都必须是 `&'a T`，且所有字段的 `'a` 与 `T` 一致
+        let Type::Reference(reference) = &field.ty else {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "account fields must be references, e.g. `&'a AccountView`",
+            ));
+        };
+        let Some(lifetime) = reference.lifetime.clone() else {
+            return Err(Error::new_spanned(
+                reference,
+                "account fields need an explicit lifetime",
+            ));
+        };
+        let elem = (*reference.elem).clone();
+        match &account {
+            None => account = Some((lifetime, elem)),
+            Some((expected_lifetime, expected_elem)) => {
+                if expected_lifetime != &lifetime
+                    || expected_elem.to_token_stream().to_string()
+                        != elem.to_token_stream().to_string()
+                {
+                    return Err(Error::new_spanned(
+                        reference,
+                        "all account fields must share the same reference type",
+                    ));
+                }
+            }
+        }
+
+        let mut constraints = Constraints::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("account"))
+        {
+            for constraint in
+                attr.parse_args_with(Punctuated::<Constraint, Token![,]>::parse_terminated)?
+            {
+                constraints.push(constraint)?;
+            }
+        }
+        checks.push(constraints.expand(&ident)?);
+        let (name, signer, writable) =
+            (ident.to_string(), constraints.signer, constraints.writable);
+        specs.push(quote! {
+            ::escrow_core::AccountSpec { name: #name, signer: #signer, writable: #writable }
+        });
+        idents.push(ident);
+    }
+
+    let Some((lifetime, elem)) = account else {
+        return Err(Error::new_spanned(
+            &input,
+            "#[derive(Accounts)] requires at least one account field",
+        ));
+    };
+
+    let name = &input.ident;
+    let count = idents.len();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// 固定账户的顺序与签名 / 可写标志
+            pub const ACCOUNTS: [::escrow_core::AccountSpec; #count] = [#(#specs),*];
+
+            /// 按声明顺序取出账户并执行 `#[account(...)]` 约束，返回剩余账户
+            #[inline(always)]
+            pub fn try_accounts(
+                __accounts: &#lifetime [#elem],
+            ) -> ::core::result::Result<(Self, &#lifetime [#elem]), ::escrow_core::Error> {
+                ::escrow_core::validate::account_flags(__accounts, &Self::ACCOUNTS)?;
+
+                let [#(#idents,)* __remaining @ ..] = __accounts else {
+                    return ::core::result::Result::Err(::escrow_core::Error::NotEnoughAccountKeys);
+                };
+
+                #(#checks)*
+
+                ::core::result::Result::Ok((Self { #(#idents),* }, __remaining))
+            }
+        }
+    })
+}
+
+/// `#[account(...)]` 中的一项：`flag` 或 `path = expr`
+struct Constraint {
+    path: Path,
+    value: Option<Expr>,
+}
+
+impl Parse for Constraint {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = Path::parse_mod_style(input)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { path, value })
+    }
+}
+
+#[derive(Default)]
+struct Constraints {
+    signer: bool,
+    writable: bool,
+    mint: bool,
+    owner: Option<Expr>,
+    address: Option<Expr>,
+    seeds: Option<Vec<Expr>>,
+    bump: Option<Expr>,
+    seeds_program: Option<Expr>,
+    token_mint: Option<Expr>,
+    token_authority: Option<Expr>,
+    associated_token_mint: Option<Expr>,
+    associated_token_authority: Option<Expr>,
+}
+
+impl Constraints {
+    fn push(&mut self, constraint: Constraint) -> Result<()> {
+        let Constraint { path, value } = constraint;
+        let key = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+
+        let flag = |slot: &mut bool| {
+            if value.is_some() {
+                return Err(Error::new_spanned(&path, format!("`{key}` takes no value")));
+            }
+            *slot = true;
+            Ok(())
+        };
+        let assign = |slot: &mut Option<Expr>| {
+            let Some(value) = value.clone() else {
+                return Err(Error::new_spanned(&path, format!("`{key}` needs a value")));
+            };
+            if slot.replace(value).is_some() {
+                return Err(Error::new_spanned(&path, format!("duplicate `{key}`")));
+            }
+            Ok(())
+        };
+
+        match key.as_str() {
+            "signer" => flag(&mut self.signer),
+            "writable" => flag(&mut self.writable),
+            "mint" => flag(&mut self.mint),
+            "owner" => assign(&mut self.owner),
+            "address" => assign(&mut self.address),
+            "bump" => assign(&mut self.bump),
+            "seeds::program" => assign(&mut self.seeds_program),
+            "token::mint" => assign(&mut self.token_mint),
+            "token::authority" => assign(&mut self.token_authority),
+            "associated_token::mint" => assign(&mut self.associated_token_mint),
+            "associated_token::authority" => assign(&mut self.associated_token_authority),
+            "seeds" => {
+                let Some(Expr::Array(array)) = &value else {
+                    return Err(Error::new_spanned(&path, "`seeds` must be an array `[..]`"));
+                };
+                if self.seeds.is_some() {
+                    return Err(Error::new_spanned(&path, "duplicate `seeds`"));
+                }
+                self.seeds = Some(array.elems.iter().cloned().collect());
+                Ok(())
+            }
+            _ => Err(Error::new_spanned(
+                &path,
+                format!("unknown account constraint `{key}`"),
+            )),
+        }
+    }
+
+    fn expand(&self, field: &syn::Ident) -> Result<TokenStream2> {
+        let validate = quote!(::escrow_core::validate);
+        let mut checks = Vec::new();
+
+        if let Some(owner) = &self.owner {
+            checks
+                .push(quote!(#validate::owned_by(#field, ::escrow_core::AsKey::as_key(&#owner))?;));
+        }
+        if let Some(address) = &self.address {
+            checks.push(
+                quote!(#validate::address(#field, ::escrow_core::AsKey::as_key(&#address))?;),
+            );
+        }
+        if self.mint {
+            checks.push(quote!(#validate::mint(#field)?;));
+        }
+
+        match &self.seeds {
+            Some(seeds) => {
+                let program = match &self.seeds_program {
+                    Some(program) => quote!(#program),
+                    None => quote!(crate::ID),
+                };
+                let program = quote!(::escrow_core::AsKey::as_key(&#program));
+                checks.push(match &self.bump {
+                    Some(bump) => quote! {
+                        #validate::program_address(#field, &[#(#seeds,)* &[#bump]], #program)?;
+                    },
+                    None => quote! {
+                        #validate::find_program_address(#field, &[#(#seeds),*], #program)?;
+                    },
+                });
+            }
+            None => {
+                if let Some(expr) = self.bump.as_ref().or(self.seeds_program.as_ref()) {
+                    return Err(Error::new_spanned(
+                        expr,
+                        "`bump` and `seeds::program` require `seeds`",
+                    ));
+                }
+            }
+        }
+
+        let key = |field: &Expr| quote!(::escrow_core::AccountAccess::key(#field));
+        match (&self.token_mint, &self.token_authority) {
+            (Some(mint), Some(authority)) => {
+                let (mint, authority) = (key(mint), key(authority));
+                checks.push(quote!(#validate::token_account(#field, #authority, #mint)?;));
+            }
+            (Some(mint), None) => {
+                let mint = key(mint);
+                checks.push(quote!(#validate::token_account_mint(#field, #mint)?;));
+            }
+            (None, Some(authority)) => {
+                let authority = key(authority);
+                checks.push(quote!(#validate::token_account_owner(#field, #authority)?;));
+            }
+            (None, None) => {}
+        }
+
+        match (
+            &self.associated_token_mint,
+            &self.associated_token_authority,
+        ) {
+            (Some(mint), Some(authority)) => {
+                let (mint, authority) = (key(mint), key(authority));
+                checks
+                    .push(quote!(#validate::associated_token_account(#field, #authority, #mint)?;));
+            }
+            (Some(expr), None) | (None, Some(expr)) => {
+                return Err(Error::new_spanned(
+                    expr,
+                    "`associated_token::mint` and `associated_token::authority` must be used together",
+                ));
+            }
+            (None, None) => {}
+        }
+
+        Ok(quote!(#(#checks)*))
+    }
+}
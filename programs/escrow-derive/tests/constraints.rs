@@ -0,0 +1,251 @@
+//! `#[derive(Accounts)]` 展开后的检查：用链下的模拟账户逐条验证每种约束
+
+use escrow_core::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, AccountAccess, AccountFlagError, AccountSpec, Error,
+    TOKEN_PROGRAM_ID,
+    validate::{MINT_LEN, TOKEN_ACCOUNT_LEN},
+};
+use escrow_derive::Accounts;
+
+const ID: [u8; 32] = [7; 32];
+const MINT: [u8; 32] = [8; 32];
+
+/// 链下模拟账户；PDA 推导用一个确定性的折叠代替 sha256，只需与自身一致
+struct Account {
+    key: [u8; 32],
+    signer: bool,
+    writable: bool,
+    owner: [u8; 32],
+    data: Vec<u8>,
+}
+
+fn derive_address(seeds: &[&[u8]], program: &[u8; 32]) -> [u8; 32] {
+    let mut address = [0u8; 32];
+    let bytes = seeds.iter().flat_map(|seed| seed.iter().chain(&[0xff]));
+    for (i, byte) in bytes.chain(program).enumerate() {
+        address[i % 32] = address[i % 32].wrapping_mul(31).wrapping_add(*byte);
+    }
+    address
+}
+
+impl AccountAccess for Account {
+    type Data<'a> = &'a [u8];
+
+    fn key(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    fn is_owned_by(&self, program: &[u8; 32]) -> bool {
+        &self.owner == program
+    }
+
+    fn lamports(&self) -> u64 {
+        1
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn try_data(&self) -> Result<Self::Data<'_>, Error> {
+        Ok(&self.data)
+    }
+
+    fn create_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> Result<[u8; 32], Error> {
+        Ok(derive_address(seeds, program))
+    }
+
+    fn find_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> ([u8; 32], u8) {
+        let bump = [255];
+        let seeds = [seeds, &[&bump[..]]].concat();
+        (derive_address(&seeds, program), bump[0])
+    }
+}
+
+#[derive(Accounts)]
+#[allow(dead_code)]
+struct Swap<'a> {
+    #[account(signer, writable)]
+    user: &'a Account,
+    #[account(writable, owner = ID, seeds = [b"state", user.key()], bump = 254)]
+    state: &'a Account,
+    #[account(mint, address = MINT)]
+    mint: &'a Account,
+    #[account(writable, token::mint = mint, token::authority = user)]
+    user_token: &'a Account,
+    #[account(associated_token::mint = mint, associated_token::authority = state)]
+    vault: &'a Account,
+    #[account(seeds = [b"config"], seeds::program = ID)]
+    config: &'a Account,
+    program: &'a Account,
+}
+
+// 账户在 `Swap` 中的位置
+const USER: usize = 0;
+const STATE: usize = 1;
+const MINT_SLOT: usize = 2;
+const USER_TOKEN: usize = 3;
+const VAULT: usize = 4;
+const CONFIG: usize = 5;
+
+fn account(key: [u8; 32]) -> Account {
+    Account {
+        key,
+        signer: false,
+        writable: false,
+        owner: [0; 32],
+        data: Vec::new(),
+    }
+}
+
+fn token_account(key: [u8; 32], mint: &[u8; 32], owner: &[u8; 32]) -> Account {
+    let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+    data[0..32].copy_from_slice(mint);
+    data[32..64].copy_from_slice(owner);
+    Account {
+        owner: TOKEN_PROGRAM_ID,
+        data,
+        ..account(key)
+    }
+}
+
+/// 满足全部约束的账户，外加一个剩余账户
+fn accounts() -> Vec<Account> {
+    let user = [1; 32];
+    let state = derive_address(&[b"state", &user, &[254]], &ID);
+    let vault = derive_address(
+        &[&state, &TOKEN_PROGRAM_ID, &MINT, &[255]],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    );
+    vec![
+        Account {
+            signer: true,
+            writable: true,
+            ..account(user)
+        },
+        Account {
+            writable: true,
+            owner: ID,
+            ..account(state)
+        },
+        Account {
+            owner: TOKEN_PROGRAM_ID,
+            data: vec![0; MINT_LEN],
+            ..account(MINT)
+        },
+        Account {
+            writable: true,
+            ..token_account([3; 32], &MINT, &user)
+        },
+        token_account(vault, &MINT, &state),
+        account(derive_address(&[b"config", &[255]], &ID)),
+        account([9; 32]),
+        account([10; 32]),
+    ]
+}
+
+fn check(accounts: &[Account]) -> Result<usize, Error> {
+    Swap::try_accounts(accounts).map(|(_, remaining)| remaining.len())
+}
+
+#[test]
+fn specs_follow_flags() {
+    assert_eq!(Swap::ACCOUNTS.len(), 7);
+    assert_eq!(Swap::ACCOUNTS[USER], AccountSpec::writable_signer("user"));
+    assert_eq!(Swap::ACCOUNTS[STATE], AccountSpec::writable("state"));
+    assert_eq!(Swap::ACCOUNTS[MINT_SLOT], AccountSpec::readonly("mint"));
+    assert_eq!(Swap::ACCOUNTS[6], AccountSpec::readonly("program"));
+}
+
+#[test]
+fn valid_accounts_pass_and_return_the_rest() {
+    assert_eq!(check(&accounts()), Ok(1));
+    assert_eq!(check(&accounts()[..7]), Ok(0));
+    assert_eq!(check(&accounts()[..6]), Err(Error::NotEnoughAccountKeys));
+}
+
+#[test]
+fn signer_and_writable() {
+    let mut accounts = accounts();
+    accounts[USER].signer = false;
+    assert_eq!(
+        check(&accounts),
+        Err(AccountFlagError::MissingSigner(USER as u8).into())
+    );
+
+    let mut accounts = self::accounts();
+    accounts[USER_TOKEN].writable = false;
+    assert_eq!(
+        check(&accounts),
+        Err(AccountFlagError::NotWritable(USER_TOKEN as u8).into())
+    );
+}
+
+#[test]
+fn owner() {
+    let mut accounts = accounts();
+    accounts[STATE].owner = [0; 32];
+    assert_eq!(check(&accounts), Err(Error::InvalidAccountOwner));
+}
+
+#[test]
+fn seeds_with_and_without_bump() {
+    let mut accounts = accounts();
+    accounts[STATE].key = derive_address(&[b"state", &[1; 32], &[253]], &ID);
+    assert_eq!(check(&accounts), Err(Error::InvalidSeeds));
+
+    // 未给出 bump 时只接受规范 bump
+    let mut accounts = self::accounts();
+    accounts[CONFIG].key = derive_address(&[b"config", &[254]], &ID);
+    assert_eq!(check(&accounts), Err(Error::InvalidSeeds));
+}
+
+#[test]
+fn address_and_mint() {
+    let mut accounts = accounts();
+    accounts[MINT_SLOT].key = [11; 32];
+    assert_eq!(check(&accounts), Err(Error::InvalidArgument));
+
+    let mut accounts = self::accounts();
+    accounts[MINT_SLOT].data.push(0);
+    assert_eq!(check(&accounts), Err(Error::InvalidAccountData));
+}
+
+#[test]
+fn token_mint_and_authority() {
+    let mut accounts = accounts();
+    accounts[USER_TOKEN].data[0..32].copy_from_slice(&[11; 32]);
+    assert_eq!(check(&accounts), Err(Error::InvalidAccountData));
+
+    let mut accounts = self::accounts();
+    accounts[USER_TOKEN].data[32..64].copy_from_slice(&[11; 32]);
+    assert_eq!(check(&accounts), Err(Error::InvalidAccountData));
+
+    // 不属于 SPL Token 程序的账户即使内容相同也不接受
+    let mut accounts = self::accounts();
+    accounts[USER_TOKEN].owner = ID;
+    assert_eq!(check(&accounts), Err(Error::InvalidAccountData));
+}
+
+#[test]
+fn associated_token_address() {
+    let state = accounts()[STATE].key;
+
+    // 内容正确但不在 ATA 地址上
+    let mut accounts = accounts();
+    accounts[VAULT] = token_account([12; 32], &MINT, &state);
+    assert_eq!(check(&accounts), Err(Error::InvalidSeeds));
+
+    let mut accounts = self::accounts();
+    let vault = accounts[VAULT].key;
+    accounts[VAULT] = token_account(vault, &MINT, &[11; 32]);
+    assert_eq!(check(&accounts), Err(Error::InvalidAccountData));
+}
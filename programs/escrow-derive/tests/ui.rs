@@ -0,0 +1,6 @@
+//! 写错的 `#[account(...)]` 必须在编译期报错，而不是生成缺少检查的代码
+
+#[test]
+fn invalid_attributes_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,11 @@
+use escrow_derive::Accounts;
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    #[account(mint)]
+    mint: &'a u8,
+    #[account(writable, associated_token::mint = mint)]
+    vault: &'a u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use escrow_derive::Accounts;
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    #[account(writable, bump = 255)]
+    state: &'a u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use escrow_derive::Accounts;
+
+const A: [u8; 32] = [1; 32];
+const B: [u8; 32] = [2; 32];
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    #[account(address = A)]
+    #[account(address = B)]
+    program: &'a u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use escrow_derive::Accounts;
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    #[account(signer = true)]
+    user: &'a u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use escrow_derive::Accounts;
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    #[account(owner)]
+    state: &'a u8,
+}
+
+fn main() {}
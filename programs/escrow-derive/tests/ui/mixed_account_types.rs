@@ -0,0 +1,9 @@
+use escrow_derive::Accounts;
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    user: &'a u8,
+    state: &'a u16,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use escrow_derive::Accounts;
+
+#[derive(Accounts)]
+enum Accounts<'a> {
+    User(&'a u8),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use escrow_derive::Accounts;
+
+const SEEDS: &[&[u8]] = &[b"state"];
+
+#[derive(Accounts)]
+struct Accounts<'a> {
+    #[account(seeds = SEEDS)]
+    state: &'a u8,
+}
+
+fn main() {}
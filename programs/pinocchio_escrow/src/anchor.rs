@@ -0,0 +1,57 @@
+/*
+Anchor 兼容模式 (`anchor` feature)，Anchor 程序与 TS 客户端无需改动即可调用本程序：
+
+- 指令：除单字节判别符外，同时接受 `sha256("global:<name>")[..8]`，名称取自 `idl::INSTRUCTIONS`；
+  参数均为定长小端字段，与 Borsh 编码一致；
+- 账户：`Escrow` 开头写入 `sha256("account:Escrow")[..8]`，Anchor 客户端可直接 fetch / 解码。
+  Config 与 MintPolicy 不变。
+
+判别符在编译期算出，未启用 feature 时也可引用 (例如拼装 Anchor 格式的指令)。
+ */
+
+use crate::idl;
+
+/// 本次编译是否启用了 Anchor 兼容模式
+pub const ENABLED: bool = cfg!(feature = "anchor");
+
+/// `Escrow` 的 Anchor 账户判别符 (布局在 `escrow_core` 中，随 `escrow-core/anchor` 写入)
+pub use escrow_core::ESCROW_DISCRIMINATOR;
+
+/// `(Anchor 判别符, 单字节判别符)`，顺序同 `idl::INSTRUCTIONS`
+pub static INSTRUCTIONS: [([u8; 8], u8); idl::INSTRUCTIONS.len()] = {
+    let mut table = [([0; 8], 0); idl::INSTRUCTIONS.len()];
+    let mut i = 0;
+    while i < table.len() {
+        let instruction = &idl::INSTRUCTIONS[i];
+        table[i] = (
+            instruction.anchor_discriminator(),
+            instruction.discriminator,
+        );
+        i += 1;
+    }
+    table
+};
+
+// 两种编码不会互相混淆：Anchor 判别符的首字节要么不是任何单字节判别符，
+// 要么就是同一条指令的 (refund)，此时两种解读落到同一条指令
+const _: () = {
+    let mut i = 0;
+    while i < INSTRUCTIONS.len() {
+        let mut j = 0;
+        while j < INSTRUCTIONS.len() {
+            assert!(i == j || INSTRUCTIONS[i].0[0] != INSTRUCTIONS[j].1);
+            j += 1;
+        }
+        i += 1;
+    }
+};
+
+/// 数据以某条指令的 Anchor 判别符开头时，返回对应的单字节判别符与其后的参数
+#[inline(always)]
+pub fn split_instruction(data: &[u8]) -> Option<(&'static u8, &[u8])> {
+    let (prefix, args) = data.split_first_chunk::<8>()?;
+    INSTRUCTIONS
+        .iter()
+        .find(|(anchor, _)| anchor == prefix)
+        .map(|(_, discriminator)| (discriminator, args))
+}
@@ -0,0 +1,205 @@
+/*
+供其他链上程序通过 CPI 调用本程序的构造器 (启用 `cpi` feature，同时关闭 `entrypoint!`)。
+
+账户顺序与签名 / 可写标志取自各指令的 `ACCOUNTS`，判别符取自 `DISCRIMINATOR`，
+与处理器共用同一份定义；`remaining` 按处理器约定的顺序附带可选的尾部账户
+(mint 策略、事件账户、收益金库或推荐人 ATA)，其标志沿用传入账户本身的状态；
+跳过某个可选账户时在该位置传入本程序的账户。
+ */
+
+use pinocchio::{
+    AccountView, ProgramResult,
+    cpi::{Signer, invoke_signed_with_bounds},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+};
+
+use crate::{AccountSpec, Make, MakeInstructionData, Refund, Take, TakeInstructionData};
+
+/// 单次 CPI 最多传入的账户数 (最长的固定账户列表 Take + 尾部账户)
+pub const MAX_CPI_ACCOUNTS: usize = Take::ACCOUNTS.len() + 8;
+
+pub struct MakeCpi<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub maker_ata_a: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub config: &'a AccountView,
+    pub remaining: &'a [&'a AccountView],
+    pub args: MakeInstructionData,
+}
+
+impl MakeCpi<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 1 + MakeInstructionData::LEN];
+        data[0] = *Make::DISCRIMINATOR;
+        data[1..].copy_from_slice(&self.args.to_bytes(self.maker.address()));
+
+        invoke_escrow(
+            &Make::ACCOUNTS,
+            [
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.mint_b,
+                self.maker_ata_a,
+                self.vault,
+                self.system_program,
+                self.token_program,
+                self.associated_token_program,
+                self.config,
+            ],
+            self.remaining,
+            &data,
+            signers,
+        )
+    }
+}
+
+pub struct TakeCpi<'a> {
+    pub taker: &'a AccountView,
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub taker_ata_a: &'a AccountView,
+    pub taker_ata_b: &'a AccountView,
+    pub maker_ata_b: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub rent_recipient: &'a AccountView,
+    pub proceeds_recipient: &'a AccountView,
+    pub config: &'a AccountView,
+    pub remaining: &'a [&'a AccountView],
+    /// 不限制成交条款时传 `TakeInstructionData::UNCHECKED`
+    pub args: TakeInstructionData,
+}
+
+impl TakeCpi<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 1 + TakeInstructionData::LEN];
+        data[0] = *Take::DISCRIMINATOR;
+        data[1..].copy_from_slice(&self.args.to_bytes());
+
+        invoke_escrow(
+            &Take::ACCOUNTS,
+            [
+                self.taker,
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.mint_b,
+                self.vault,
+                self.taker_ata_a,
+                self.taker_ata_b,
+                self.maker_ata_b,
+                self.system_program,
+                self.token_program,
+                self.associated_token_program,
+                self.rent_recipient,
+                self.proceeds_recipient,
+                self.config,
+            ],
+            self.remaining,
+            &data,
+            signers,
+        )
+    }
+}
+
+pub struct RefundCpi<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub maker_ata_a: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub rent_recipient: &'a AccountView,
+    pub remaining: &'a [&'a AccountView],
+}
+
+impl RefundCpi<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_escrow(
+            &Refund::ACCOUNTS,
+            [
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.vault,
+                self.maker_ata_a,
+                self.associated_token_program,
+                self.token_program,
+                self.system_program,
+                self.rent_recipient,
+            ],
+            self.remaining,
+            &[*Refund::DISCRIMINATOR],
+            signers,
+        )
+    }
+}
+
+/// 按 `specs` 生成固定账户的元数据，拼接尾部账户后调用本程序
+fn invoke_escrow<const N: usize>(
+    specs: &[AccountSpec; N],
+    fixed: [&AccountView; N],
+    remaining: &[&AccountView],
+    data: &[u8],
+    signers: &[Signer],
+) -> ProgramResult {
+    let len = N + remaining.len();
+    if len > MAX_CPI_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // 超出 `len` 的槽位只用于填满数组，不会传给运行时
+    let views: [&AccountView; MAX_CPI_ACCOUNTS] = core::array::from_fn(|i| {
+        if i < N {
+            fixed[i]
+        } else {
+            remaining.get(i - N).copied().unwrap_or(fixed[0])
+        }
+    });
+    let metas: [InstructionAccount; MAX_CPI_ACCOUNTS] = core::array::from_fn(|i| {
+        if i < N {
+            InstructionAccount::new(views[i].address(), specs[i].writable, specs[i].signer)
+        } else {
+            InstructionAccount::from(views[i])
+        }
+    });
+
+    invoke_signed_with_bounds::<MAX_CPI_ACCOUNTS>(
+        &InstructionView {
+            program_id: &crate::ID,
+            accounts: &metas[..len],
+            data,
+        },
+        &views[..len],
+        signers,
+    )
+}
@@ -0,0 +1,62 @@
+/*
+链下构建的 SPL Token CPI 记录。
+
+链下编译时 CPI 是空操作，模糊测试看不到程序实际发起了哪些转账。
+`TokenProgram` 在发起 Transfer / CloseAccount 之前把账户、数量与 PDA 签名记到当前线程，
+测试用 `take` 取出后检查授权与 mint。只在打开 `fuzz` feature 的链下构建中存在，
+程序、客户端与其他测试的构建都不包含本模块。
+ */
+
+use std::cell::RefCell;
+
+use pinocchio::{AccountView, Address, cpi::Seed};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenInstruction {
+    Transfer { amount: u64 },
+    CloseAccount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenCpi {
+    pub instruction: TokenInstruction,
+    pub source: Address,      // Transfer `from`, or the account being closed
+    pub destination: Address, // Transfer `to`, or the lamports recipient
+    pub authority: Address,
+    /// `invoke_signed` 的种子在本程序下推导出的 PDA；直接 `invoke` 或种子无效时为 `None`
+    pub pda_signer: Option<Address>,
+}
+
+thread_local! {
+    static TRACE: RefCell<Vec<TokenCpi>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 取出并清空当前线程的记录
+pub fn take() -> Vec<TokenCpi> {
+    TRACE.with(|trace| trace.take())
+}
+
+pub(crate) fn record(
+    instruction: TokenInstruction,
+    source: &AccountView,
+    destination: &AccountView,
+    authority: &AccountView,
+    signer_seeds: &[Seed],
+) {
+    let pda_signer = match signer_seeds {
+        [] => None,
+        seeds => {
+            let seeds: Vec<&[u8]> = seeds.iter().map(|seed| &**seed).collect();
+            Address::create_program_address(&seeds, &crate::ID).ok()
+        }
+    };
+    TRACE.with(|trace| {
+        trace.borrow_mut().push(TokenCpi {
+            instruction,
+            source: *source.address(),
+            destination: *destination.address(),
+            authority: *authority.address(),
+            pda_signer,
+        })
+    });
+}
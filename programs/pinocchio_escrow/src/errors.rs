@@ -0,0 +1,2 @@
+// 错误码与两代程序共用，定义在 escrow-core
+pub use escrow_core::{AccountFlagError, EscrowError};
@@ -0,0 +1,310 @@
+/*
+每次状态变化后发出的紧凑二进制事件，供索引器直接解析，无需反推指令数据与余额变化。
+
+两种通道，可同时启用：
+- `events` (默认)：通过 `sol_log_data` 写入日志；
+- `cpi-events`：以 event authority PDA 签名自调用 `EmitEvent`，事件出现在内部指令中，
+  不会像日志那样在高负载下被截断。
+
+编码布局：1 字节事件判别符 + 定长小端字段，`Event::decode` 供链下使用。
+ */
+
+use core::marker::PhantomData;
+
+use escrow_core::RemainingAccounts;
+use pinocchio::{AccountView, Address, ProgramResult, error::ProgramError};
+
+// --- 自调用事件的签名 PDA ---
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"event_authority";
+
+/// 与 Anchor 相同，bump 与地址在编译期确定，每次发事件不再 `find_program_address`
+/// (程序 ID 变化后须同步更新 bump，`escrow-client` 的测试会比对二者)
+pub const EVENT_AUTHORITY_BUMP: u8 = 255;
+pub const EVENT_AUTHORITY_ADDRESS: Address = Address::derive_address_const(
+    &[EVENT_AUTHORITY_SEED],
+    Some(EVENT_AUTHORITY_BUMP),
+    &crate::ID,
+);
+
+/// 成功创建报价 (Make)
+pub struct OfferMade {
+    pub escrow: Address,
+    pub maker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub seed: u64,
+    pub amount: u64,
+    pub receive: u64,
+    pub referral_bps: u16,
+    pub settlement: u8,
+}
+
+/// 报价成交 (Take)
+pub struct OfferTaken {
+    pub escrow: Address,
+    pub maker: Address,
+    pub taker: Address,
+    pub amount_a: u64, // Token A sent to the taker
+    pub amount_b: u64, // Token B sent to the proceeds recipient (or proceeds vault)
+    pub fee: u64,      // Token B sent to the referrer
+}
+
+/// 报价被创建者取消 (Refund)
+pub struct OfferRefunded {
+    pub escrow: Address,
+    pub maker: Address,
+    pub amount_a: u64, // Token A returned to the maker
+}
+
+/// Claim 模式下创建者领取收益 (Claim)
+pub struct ProceedsClaimed {
+    pub escrow: Address,
+    pub maker: Address,
+    pub amount_b: u64, // Token B sent to the proceeds recipient
+}
+
+impl OfferMade {
+    pub const DISCRIMINATOR: u8 = 0;
+    pub const LEN: usize = 1 + 32 * 4 + 8 * 3 + 2 + 1;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0] = Self::DISCRIMINATOR;
+        bytes[1..33].copy_from_slice(self.escrow.as_ref());
+        bytes[33..65].copy_from_slice(self.maker.as_ref());
+        bytes[65..97].copy_from_slice(self.mint_a.as_ref());
+        bytes[97..129].copy_from_slice(self.mint_b.as_ref());
+        bytes[129..137].copy_from_slice(&self.seed.to_le_bytes());
+        bytes[137..145].copy_from_slice(&self.amount.to_le_bytes());
+        bytes[145..153].copy_from_slice(&self.receive.to_le_bytes());
+        bytes[153..155].copy_from_slice(&self.referral_bps.to_le_bytes());
+        bytes[155] = self.settlement;
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            escrow: read_address(bytes, 1),
+            maker: read_address(bytes, 33),
+            mint_a: read_address(bytes, 65),
+            mint_b: read_address(bytes, 97),
+            seed: read_u64(bytes, 129),
+            amount: read_u64(bytes, 137),
+            receive: read_u64(bytes, 145),
+            referral_bps: u16::from_le_bytes(bytes[153..155].try_into().unwrap()),
+            settlement: bytes[155],
+        }
+    }
+}
+
+impl OfferTaken {
+    pub const DISCRIMINATOR: u8 = 1;
+    pub const LEN: usize = 1 + 32 * 3 + 8 * 3;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0] = Self::DISCRIMINATOR;
+        bytes[1..33].copy_from_slice(self.escrow.as_ref());
+        bytes[33..65].copy_from_slice(self.maker.as_ref());
+        bytes[65..97].copy_from_slice(self.taker.as_ref());
+        bytes[97..105].copy_from_slice(&self.amount_a.to_le_bytes());
+        bytes[105..113].copy_from_slice(&self.amount_b.to_le_bytes());
+        bytes[113..121].copy_from_slice(&self.fee.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            escrow: read_address(bytes, 1),
+            maker: read_address(bytes, 33),
+            taker: read_address(bytes, 65),
+            amount_a: read_u64(bytes, 97),
+            amount_b: read_u64(bytes, 105),
+            fee: read_u64(bytes, 113),
+        }
+    }
+}
+
+impl OfferRefunded {
+    pub const DISCRIMINATOR: u8 = 2;
+    pub const LEN: usize = 1 + 32 * 2 + 8;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0] = Self::DISCRIMINATOR;
+        bytes[1..33].copy_from_slice(self.escrow.as_ref());
+        bytes[33..65].copy_from_slice(self.maker.as_ref());
+        bytes[65..73].copy_from_slice(&self.amount_a.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            escrow: read_address(bytes, 1),
+            maker: read_address(bytes, 33),
+            amount_a: read_u64(bytes, 65),
+        }
+    }
+}
+
+impl ProceedsClaimed {
+    pub const DISCRIMINATOR: u8 = 3;
+    pub const LEN: usize = 1 + 32 * 2 + 8;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0] = Self::DISCRIMINATOR;
+        bytes[1..33].copy_from_slice(self.escrow.as_ref());
+        bytes[33..65].copy_from_slice(self.maker.as_ref());
+        bytes[65..73].copy_from_slice(&self.amount_b.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            escrow: read_address(bytes, 1),
+            maker: read_address(bytes, 33),
+            amount_b: read_u64(bytes, 65),
+        }
+    }
+}
+
+/// 最长事件的字节数 (自调用时的指令数据缓冲区大小)
+pub const MAX_EVENT_LEN: usize = OfferMade::LEN;
+
+/// 链下解码入口：按判别符与长度还原事件
+pub enum Event {
+    OfferMade(OfferMade),
+    OfferTaken(OfferTaken),
+    OfferRefunded(OfferRefunded),
+    ProceedsClaimed(ProceedsClaimed),
+}
+
+impl Event {
+    pub fn decode(bytes: &[u8]) -> Result<Self, ProgramError> {
+        match (bytes.first().copied(), bytes.len()) {
+            (Some(OfferMade::DISCRIMINATOR), OfferMade::LEN) => {
+                Ok(Self::OfferMade(OfferMade::from_bytes(bytes)))
+            }
+            (Some(OfferTaken::DISCRIMINATOR), OfferTaken::LEN) => {
+                Ok(Self::OfferTaken(OfferTaken::from_bytes(bytes)))
+            }
+            (Some(OfferRefunded::DISCRIMINATOR), OfferRefunded::LEN) => {
+                Ok(Self::OfferRefunded(OfferRefunded::from_bytes(bytes)))
+            }
+            (Some(ProceedsClaimed::DISCRIMINATOR), ProceedsClaimed::LEN) => {
+                Ok(Self::ProceedsClaimed(ProceedsClaimed::from_bytes(bytes)))
+            }
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// 事件发送器：按启用的 feature 将事件写入日志和/或自调用通道。
+///
+/// 启用 `cpi-events` 时，指令需在账户列表中额外传入 `[event_authority, program]`。
+pub struct EventEmitter<'a> {
+    #[cfg(feature = "cpi-events")]
+    pub event_authority: &'a AccountView,
+    #[cfg(feature = "cpi-events")]
+    pub program: &'a AccountView,
+    _accounts: PhantomData<&'a AccountView>,
+}
+
+impl<'a> EventEmitter<'a> {
+    /// 从尾部账户中取出自调用所需的 `[event_authority, program]`
+    #[cfg(feature = "cpi-events")]
+    pub fn parse(remaining: &mut RemainingAccounts<'a, AccountView>) -> Result<Self, ProgramError> {
+        let event_authority = remaining.required()?;
+        let program = remaining.required()?;
+
+        if event_authority.address() != &EVENT_AUTHORITY_ADDRESS {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if program.address() != &crate::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok(Self {
+            event_authority,
+            program,
+            _accounts: PhantomData,
+        })
+    }
+
+    /// 未启用 `cpi-events` 时不占用任何账户
+    #[cfg(not(feature = "cpi-events"))]
+    #[inline(always)]
+    pub fn parse(
+        _remaining: &mut RemainingAccounts<'a, AccountView>,
+    ) -> Result<Self, ProgramError> {
+        Ok(Self {
+            _accounts: PhantomData,
+        })
+    }
+
+    #[inline(always)]
+    pub fn emit(&self, event: &[u8]) -> ProgramResult {
+        #[cfg(feature = "events")]
+        log_event(event);
+
+        #[cfg(feature = "cpi-events")]
+        self.emit_cpi(event)?;
+
+        #[cfg(not(any(feature = "events", feature = "cpi-events")))]
+        let _ = event;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cpi-events")]
+    fn emit_cpi(&self, event: &[u8]) -> ProgramResult {
+        use pinocchio::{
+            cpi::{Seed, Signer, invoke_signed},
+            instruction::{InstructionAccount, InstructionView},
+        };
+
+        let mut data = [0u8; 1 + MAX_EVENT_LEN];
+        data[0] = *crate::EmitEvent::DISCRIMINATOR;
+        data[1..1 + event.len()].copy_from_slice(event);
+
+        let bump_binding = [EVENT_AUTHORITY_BUMP];
+        let seeds = [Seed::from(EVENT_AUTHORITY_SEED), Seed::from(&bump_binding)];
+
+        invoke_signed(
+            &InstructionView {
+                program_id: &crate::ID,
+                data: &data[..1 + event.len()],
+                accounts: &[InstructionAccount::readonly_signer(
+                    self.event_authority.address(),
+                )],
+            },
+            &[self.event_authority],
+            &[Signer::from(&seeds)],
+        )
+    }
+}
+
+/// 通过 `sol_log_data` 输出一条事件
+#[cfg(feature = "events")]
+#[inline(always)]
+fn log_event(data: &[u8]) {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        let fields: [&[u8]; 1] = [data];
+        pinocchio::syscalls::sol_log_data(fields.as_ptr() as *const u8, fields.len() as u64);
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    core::hint::black_box(data);
+}
+
+#[inline(always)]
+fn read_address(bytes: &[u8], offset: usize) -> Address {
+    Address::new_from_array(bytes[offset..offset + 32].try_into().unwrap())
+}
+
+#[inline(always)]
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
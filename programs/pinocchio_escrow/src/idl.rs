@@ -0,0 +1,346 @@
+/*
+接口描述 (IDL) 的唯一数据源：指令 (判别符、账户、参数)、账户布局、事件与错误码。
+
+判别符与账户列表直接引用各指令的 `DISCRIMINATOR` / `ACCOUNTS`；参数与账户字段的总宽度
+在编译期与 `MakeInstructionData::LEN`、`Escrow::LEN` 等常量核对。`escrow-idl` 将其渲染为
+Anchor 0.30 格式的 JSON (Codama 可通过 `@codama/nodes-from-anchor` 导入)。
+ */
+
+use crate::{
+    AcceptAdmin, AccountSpec, Claim, Config, EmitEvent, Escrow, EscrowError, InitConfig, Make,
+    MakeInstructionData, MintPolicy, OfferMade, OfferRefunded, OfferTaken, ProceedsClaimed,
+    QuoteOffer, Refund, SetMintPolicy, SetPause, Take, TakeInstructionData, TransferAdmin,
+};
+
+use IdlType::{Address, Bool, U8, U16, U64};
+
+pub const NAME: &str = "pinocchio_escrow";
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 字段类型，均按小端定长编码
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdlType {
+    U8,
+    U16,
+    U64,
+    Bool,
+    Address,
+}
+
+impl IdlType {
+    pub const fn size(self) -> usize {
+        match self {
+            Self::U8 | Self::Bool => 1,
+            Self::U16 => 2,
+            Self::U64 => 8,
+            Self::Address => 32,
+        }
+    }
+
+    /// Anchor IDL 中的类型名
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U64 => "u64",
+            Self::Bool => "bool",
+            Self::Address => "pubkey",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IdlField {
+    pub name: &'static str,
+    pub ty: IdlType,
+}
+
+const fn field(name: &'static str, ty: IdlType) -> IdlField {
+    IdlField { name, ty }
+}
+
+/// 字段按顺序紧密排列后的总字节数
+pub const fn fields_len(fields: &[IdlField]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        len += fields[i].ty.size();
+        i += 1;
+    }
+    len
+}
+
+pub struct IdlInstruction {
+    pub name: &'static str,
+    pub docs: &'static str,
+    pub discriminator: u8,
+    pub accounts: &'static [AccountSpec],
+    pub args: &'static [IdlField],
+}
+
+impl IdlInstruction {
+    /// Anchor 格式的判别符 `sha256("global:<name>")[..8]` (见 `anchor`)
+    pub const fn anchor_discriminator(&self) -> [u8; 8] {
+        escrow_core::anchor::discriminator("global", self.name)
+    }
+}
+
+/// 账户或事件的字段布局
+pub struct IdlLayout {
+    pub name: &'static str,
+    pub discriminator: &'static [u8],
+    pub fields: &'static [IdlField],
+}
+
+pub struct IdlError {
+    pub code: u32,
+    pub name: &'static str,
+    pub msg: &'static str,
+}
+
+// --- 指令参数 ---
+
+pub const MAKE_ARGS: [IdlField; 7] = [
+    field("seed", U64),
+    field("receive", U64),
+    field("amount", U64),
+    field("referral_bps", U16),
+    field("rent_recipient", Address),
+    field("proceeds_recipient", Address),
+    field("settlement", U8),
+];
+
+const _: () = assert!(fields_len(&MAKE_ARGS) == MakeInstructionData::LEN);
+
+pub const TAKE_ARGS: [IdlField; 2] = [field("max_amount_in", U64), field("min_amount_out", U64)];
+
+const _: () = assert!(fields_len(&TAKE_ARGS) == TakeInstructionData::LEN);
+
+pub const INSTRUCTIONS: &[IdlInstruction] = &[
+    IdlInstruction {
+        name: "make",
+        docs: "Create an offer: deposit `amount` of mint A and ask for `receive` of mint B. \
+               The data may stop after `amount`, `referral_bps` or `proceeds_recipient` \
+               (defaults: no referral, the maker as both recipients, push settlement). \
+               The Config PDA is the last fixed account and is required since 0.2.0. \
+               Claim-mode offers append the proceeds vault account.",
+        discriminator: *Make::DISCRIMINATOR,
+        accounts: &Make::ACCOUNTS,
+        args: &MAKE_ARGS,
+    },
+    IdlInstruction {
+        name: "take",
+        docs: "Fill an open offer; the taker pays exactly `receive`. An optional trailing account \
+               receives the referral fee in mint B, split out of `receive`, and may not belong to \
+               the taker, the maker or the proceeds recipient. Legacy 113-byte escrows can be taken \
+               too, with no referral fee. \
+               The Config PDA is the last fixed account and is required since 0.2.0. \
+               The data may be empty (no limits on the settled amounts).",
+        discriminator: *Take::DISCRIMINATOR,
+        accounts: &Take::ACCOUNTS,
+        args: &TAKE_ARGS,
+    },
+    IdlInstruction {
+        name: "refund",
+        docs: "Cancel an open offer. Claim-mode offers append the proceeds vault, mint B, the proceeds recipient \
+               and its mint B token account; any tokens sent to the proceeds vault are forwarded there.",
+        discriminator: *Refund::DISCRIMINATOR,
+        accounts: &Refund::ACCOUNTS,
+        args: &[],
+    },
+    IdlInstruction {
+        name: "claim",
+        docs: "Withdraw the proceeds of a filled claim-mode offer and close it.",
+        discriminator: *Claim::DISCRIMINATOR,
+        accounts: &Claim::ACCOUNTS,
+        args: &[],
+    },
+    IdlInstruction {
+        name: "quote_offer",
+        docs: "Read-only: return the amounts Take would settle as return data \
+               (`amount_out | amount_in | fee` as u64 LE, then `fillable` as u8). \
+               Offers are all-or-nothing and never expire, so there is no remaining amount or expiry. \
+               `with_referrer` may be omitted.",
+        discriminator: *QuoteOffer::DISCRIMINATOR,
+        accounts: &QuoteOffer::ACCOUNTS,
+        args: &[field("with_referrer", Bool)],
+    },
+    IdlInstruction {
+        name: "init_config",
+        docs: "Create the config PDA. The signer must be the program upgrade authority.",
+        discriminator: *InitConfig::DISCRIMINATOR,
+        accounts: &InitConfig::ACCOUNTS,
+        args: &[],
+    },
+    IdlInstruction {
+        name: "set_pause",
+        docs: "Set the paused instruction flags (1 = make, 2 = take).",
+        discriminator: *SetPause::DISCRIMINATOR,
+        accounts: &SetPause::ACCOUNTS,
+        args: &[field("paused", U8)],
+    },
+    IdlInstruction {
+        name: "transfer_admin",
+        docs: "Nominate a new admin; the default pubkey cancels a pending nomination.",
+        discriminator: *TransferAdmin::DISCRIMINATOR,
+        accounts: &TransferAdmin::ACCOUNTS,
+        args: &[field("new_admin", Address)],
+    },
+    IdlInstruction {
+        name: "accept_admin",
+        docs: "The nominated admin accepts the role.",
+        discriminator: *AcceptAdmin::DISCRIMINATOR,
+        accounts: &AcceptAdmin::ACCOUNTS,
+        args: &[],
+    },
+    IdlInstruction {
+        name: "set_mint_policy",
+        docs: "Allow (1) or deny (2) a mint for Make. Only routed when built with `mint-policy`.",
+        discriminator: *SetMintPolicy::DISCRIMINATOR,
+        accounts: &SetMintPolicy::ACCOUNTS,
+        args: &[field("status", U8)],
+    },
+    IdlInstruction {
+        name: "emit_event",
+        docs: "Self-CPI event sink; the data is an encoded event. Only routed when built with `cpi-events`.",
+        discriminator: *EmitEvent::DISCRIMINATOR,
+        accounts: &EmitEvent::ACCOUNTS,
+        args: &[],
+    },
+];
+
+// --- 账户布局 (只有 `anchor` feature 下的 Escrow 带账户判别符) ---
+pub const ESCROW_FIELDS: [IdlField; 11] = [
+    field("seed", U64),
+    field("maker", Address),
+    field("mint_a", Address),
+    field("mint_b", Address),
+    field("receive", U64),
+    field("bump", U8),
+    field("referral_bps", U16),
+    field("rent_recipient", Address),
+    field("proceeds_recipient", Address),
+    field("settlement", U8),
+    field("status", U8),
+];
+
+const CONFIG_FIELDS: [IdlField; 4] = [
+    field("admin", Address),
+    field("pending_admin", Address),
+    field("paused", U8),
+    field("bump", U8),
+];
+
+const MINT_POLICY_FIELDS: [IdlField; 3] = [
+    field("mint", Address),
+    field("status", U8),
+    field("bump", U8),
+];
+
+const _: () = assert!(Escrow::DISCRIMINATOR.len() + fields_len(&ESCROW_FIELDS) == Escrow::LEN);
+const _: () = assert!(fields_len(&CONFIG_FIELDS) == Config::LEN);
+const _: () = assert!(fields_len(&MINT_POLICY_FIELDS) == MintPolicy::LEN);
+
+pub const ACCOUNTS: &[IdlLayout] = &[
+    IdlLayout {
+        name: "Escrow",
+        discriminator: Escrow::DISCRIMINATOR,
+        fields: &ESCROW_FIELDS,
+    },
+    IdlLayout {
+        name: "Config",
+        discriminator: &[],
+        fields: &CONFIG_FIELDS,
+    },
+    IdlLayout {
+        name: "MintPolicy",
+        discriminator: &[],
+        fields: &MINT_POLICY_FIELDS,
+    },
+];
+
+// --- 事件 (1 字节判别符 + 字段，经 `sol_log_data` 或 `emit_event` 发出) ---
+const OFFER_MADE_FIELDS: [IdlField; 9] = [
+    field("escrow", Address),
+    field("maker", Address),
+    field("mint_a", Address),
+    field("mint_b", Address),
+    field("seed", U64),
+    field("amount", U64),
+    field("receive", U64),
+    field("referral_bps", U16),
+    field("settlement", U8),
+];
+
+const OFFER_TAKEN_FIELDS: [IdlField; 6] = [
+    field("escrow", Address),
+    field("maker", Address),
+    field("taker", Address),
+    field("amount_a", U64),
+    field("amount_b", U64),
+    field("fee", U64),
+];
+
+const OFFER_REFUNDED_FIELDS: [IdlField; 3] = [
+    field("escrow", Address),
+    field("maker", Address),
+    field("amount_a", U64),
+];
+
+const PROCEEDS_CLAIMED_FIELDS: [IdlField; 3] = [
+    field("escrow", Address),
+    field("maker", Address),
+    field("amount_b", U64),
+];
+
+const _: () = assert!(1 + fields_len(&OFFER_MADE_FIELDS) == OfferMade::LEN);
+const _: () = assert!(1 + fields_len(&OFFER_TAKEN_FIELDS) == OfferTaken::LEN);
+const _: () = assert!(1 + fields_len(&OFFER_REFUNDED_FIELDS) == OfferRefunded::LEN);
+const _: () = assert!(1 + fields_len(&PROCEEDS_CLAIMED_FIELDS) == ProceedsClaimed::LEN);
+
+pub const EVENTS: &[IdlLayout] = &[
+    IdlLayout {
+        name: "OfferMade",
+        discriminator: &[OfferMade::DISCRIMINATOR],
+        fields: &OFFER_MADE_FIELDS,
+    },
+    IdlLayout {
+        name: "OfferTaken",
+        discriminator: &[OfferTaken::DISCRIMINATOR],
+        fields: &OFFER_TAKEN_FIELDS,
+    },
+    IdlLayout {
+        name: "OfferRefunded",
+        discriminator: &[OfferRefunded::DISCRIMINATOR],
+        fields: &OFFER_REFUNDED_FIELDS,
+    },
+    IdlLayout {
+        name: "ProceedsClaimed",
+        discriminator: &[ProceedsClaimed::DISCRIMINATOR],
+        fields: &PROCEEDS_CLAIMED_FIELDS,
+    },
+];
+
+// --- 错误码 ---
+// 由 `EscrowError` 的声明生成，新增变体自动出现在这里
+const ERROR_TABLE: [IdlError; EscrowError::ALL.len()] = {
+    const EMPTY: IdlError = IdlError {
+        code: 0,
+        name: "",
+        msg: "",
+    };
+    let mut table = [EMPTY; EscrowError::ALL.len()];
+    let mut i = 0;
+    while i < table.len() {
+        let error = EscrowError::ALL[i];
+        table[i] = IdlError {
+            code: error as u32,
+            name: error.name(),
+            msg: error.msg(),
+        };
+        i += 1;
+    }
+    table
+};
+
+pub const ERRORS: &[IdlError] = &ERROR_TABLE;
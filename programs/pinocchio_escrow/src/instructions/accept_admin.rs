@@ -0,0 +1,52 @@
+/*
+两步移交管理员权限的第二步：被提名者签名确认，成为新的管理员。
+ */
+
+use escrow_core::validate;
+use pinocchio::{AccountView, Address, ProgramResult, error::ProgramError};
+
+use crate::{AccountSpec, Config, ConfigAccount, EscrowError};
+
+pub struct AcceptAdmin<'a> {
+    pub pending_admin: &'a AccountView,
+    pub config: &'a AccountView,
+}
+
+impl<'a> AcceptAdmin<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &6;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 2] = [
+        AccountSpec::readonly_signer("pending_admin"),
+        AccountSpec::writable("config"),
+    ];
+
+    pub fn try_from(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        validate::account_flags(accounts, &Self::ACCOUNTS)?;
+
+        let [pending_admin, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        ConfigAccount::check(config)?;
+
+        Ok(Self {
+            pending_admin,
+            config,
+        })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.config.try_borrow_mut()?;
+        let config = Config::load_mut(&mut data)?;
+
+        if !config.has_pending_admin() || &config.pending_admin != self.pending_admin.address() {
+            return Err(EscrowError::UnauthorizedAdmin.into());
+        }
+
+        config.set_admin(*self.pending_admin.address());
+        config.set_pending_admin(Address::default());
+
+        Ok(())
+    }
+}
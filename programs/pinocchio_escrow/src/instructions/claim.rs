@@ -0,0 +1,145 @@
+/*
+Claim 模式下，报价成交后 Token B 停留在收益金库 (escrow 的 mint_b ATA) 中：
+
+创建者签名领取，将收益金库的全部余额转给收款人 (默认为创建者) 的 ATA。
+
+关闭收益金库与托管 PDA，租金返还给租金接收者。
+ */
+
+use escrow_core::RemainingAccounts;
+use escrow_derive::Accounts;
+use pinocchio::{AccountView, ProgramResult, cpi::Seed, error::ProgramError};
+use pinocchio_token::state::TokenAccount;
+
+use crate::{
+    AccountSpec, AssociatedTokenAccount, ESCROW_SEED, Escrow, EscrowError, EventEmitter,
+    ProceedsClaimed, ProgramAccount, TokenProgram,
+};
+
+#[derive(Accounts)]
+pub struct ClaimAccounts<'a> {
+    #[account(signer, writable)]
+    pub maker: &'a AccountView,
+    #[account(writable, owner = crate::ID)]
+    pub escrow: &'a AccountView,
+    #[account(mint)]
+    pub mint_b: &'a AccountView,
+    #[account(writable, associated_token::mint = mint_b, associated_token::authority = escrow)]
+    pub proceeds_vault: &'a AccountView,
+    #[account(writable)]
+    pub proceeds_ata_b: &'a AccountView,
+    pub proceeds_recipient: &'a AccountView,
+    #[account(writable)]
+    pub rent_recipient: &'a AccountView,
+    #[account(address = pinocchio_system::ID)]
+    pub system_program: &'a AccountView,
+    #[account(address = pinocchio_token::ID)]
+    pub token_program: &'a AccountView,
+    #[account(address = pinocchio_associated_token_account::ID)]
+    pub associated_token_program: &'a AccountView,
+}
+
+pub struct Claim<'a> {
+    pub accounts: ClaimAccounts<'a>,
+    pub emitter: EventEmitter<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Claim<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let (accounts, remaining) = ClaimAccounts::try_accounts(accounts)?;
+
+        // Self-CPI event accounts (cpi-events)
+        let emitter =
+            EventEmitter::parse(&mut RemainingAccounts::new(remaining, crate::ID.as_array()))?;
+
+        // Initialize necessary accounts
+        AssociatedTokenAccount::init_if_needed(
+            accounts.proceeds_ata_b,
+            accounts.mint_b,
+            accounts.maker,
+            accounts.proceeds_recipient,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts, emitter })
+    }
+}
+
+impl<'a> Claim<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &8;
+
+    /// 固定账户，由 `ClaimAccounts` 的字段与 `#[account(signer, writable)]` 声明生成
+    pub const ACCOUNTS: [AccountSpec; 10] = ClaimAccounts::ACCOUNTS;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // Only the maker may claim, and only once the offer is filled
+        if &escrow.maker != self.accounts.maker.address() {
+            return Err(EscrowError::InvalidMaker.into());
+        }
+        if !escrow.is_claim_mode() {
+            return Err(EscrowError::InvalidSettlement.into());
+        }
+        if !escrow.is_filled() {
+            return Err(EscrowError::OfferNotFilled.into());
+        }
+        if &escrow.mint_b != self.accounts.mint_b.address() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if &escrow.rent_recipient != self.accounts.rent_recipient.address() {
+            return Err(EscrowError::InvalidRentRecipient.into());
+        }
+        if &escrow.proceeds_recipient != self.accounts.proceeds_recipient.address() {
+            return Err(EscrowError::InvalidProceedsRecipient.into());
+        }
+
+        let seed_bytes = escrow.seed;
+        let escrow_seeds = [
+            Seed::from(ESCROW_SEED),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_bytes),
+            Seed::from(&escrow.bump),
+        ];
+
+        let amount = TokenAccount::from_account_view(self.accounts.proceeds_vault)?.amount();
+
+        // Transfer from the proceeds vault to the proceeds recipient
+        if amount > 0 {
+            TokenProgram::transfer(
+                self.accounts.proceeds_vault,
+                self.accounts.proceeds_ata_b,
+                self.accounts.escrow,
+                amount,
+                &escrow_seeds,
+            )?;
+        }
+
+        // Close the proceeds vault
+        TokenProgram::close_account(
+            self.accounts.proceeds_vault,
+            self.accounts.rent_recipient,
+            self.accounts.escrow,
+            &escrow_seeds,
+        )?;
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.rent_recipient)?;
+
+        self.emitter.emit(
+            &ProceedsClaimed {
+                escrow: *self.accounts.escrow.address(),
+                maker: *self.accounts.maker.address(),
+                amount_b: amount,
+            }
+            .to_bytes(),
+        )?;
+
+        Ok(())
+    }
+}
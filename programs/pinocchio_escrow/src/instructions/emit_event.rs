@@ -0,0 +1,40 @@
+/*
+自调用事件通道的接收端 (需启用 `cpi-events` feature)。
+
+指令本身什么都不做，事件字节就是指令数据；索引器从内部指令中读取。
+只有本程序能为 event authority PDA 签名，因此要求其签名即可保证调用来自自调用 (self-CPI)。
+ */
+
+use escrow_core::validate;
+use pinocchio::{AccountView, ProgramResult, error::ProgramError};
+
+use crate::{AccountSpec, EVENT_AUTHORITY_ADDRESS};
+
+pub struct EmitEvent<'a> {
+    pub event_authority: &'a AccountView,
+}
+
+impl<'a> EmitEvent<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &9;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 1] = [AccountSpec::readonly_signer("event_authority")];
+
+    pub fn try_from(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        validate::account_flags(accounts, &Self::ACCOUNTS)?;
+
+        let [event_authority] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if event_authority.address() != &EVENT_AUTHORITY_ADDRESS {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self { event_authority })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        Ok(())
+    }
+}
@@ -1,19 +1,21 @@
+use escrow_core::{close_lamports, validate};
 use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
-use pinocchio::{AccountView, ProgramResult};
+use pinocchio::{AccountView, Address, ProgramResult};
 use pinocchio_associated_token_account::instructions::CreateIdempotent;
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::state::TokenAccount;
+use pinocchio_token::instructions::{CloseAccount, Transfer};
+
+use crate::{CONFIG_ADDRESS, Config, EscrowError};
+#[cfg(feature = "mint-policy")]
+use crate::{MINT_POLICY_SEED, MintPolicy};
 
 // --- 1. 签名者检查助手 ---
 pub struct SignerAccount;
 impl SignerAccount {
     #[inline(always)]
     pub fn check(account: &AccountView) -> Result<(), ProgramError> {
-        if !account.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        Ok(())
+        Ok(validate::signer(account)?)
     }
 }
 
@@ -44,18 +46,15 @@ impl ProgramAccount {
     /// 检查该账户是否由本程序拥有
     #[inline(always)]
     pub fn check(account: &AccountView) -> Result<(), ProgramError> {
-        if !account.owned_by(&crate::ID) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(())
+        Ok(validate::owned_by(account, crate::ID.as_array())?)
     }
 
     /// 关闭账户并回收 Lamports (常用于 Refund/Take)
     pub fn close(account: &AccountView, destination: &AccountView) -> ProgramResult {
         // 将 lamports 转移给接收者
-        let lamports = account.lamports();
+        let lamports = close_lamports(account.lamports(), destination.lamports())?;
         account.set_lamports(0);
-        destination.set_lamports(destination.lamports() + lamports);
+        destination.set_lamports(lamports);
 
         // 清理数据并将所有者重置为系统程序
         account.close()
@@ -68,12 +67,37 @@ impl MintInterface {
     #[inline(always)]
     pub fn check(account: &AccountView) -> Result<(), ProgramError> {
         // SPL Token Mint 固定长度为 82
-        // 且必须由 Token Program 拥有
-        if account.data_len() != 82 {
-            // SPL Token Mint 固定长度
-            return Err(ProgramError::InvalidAccountData);
+        Ok(validate::mint(account)?)
+    }
+}
+
+// --- 3.1 Mint 准入策略助手 (需启用 `mint-policy` feature) ---
+#[cfg(feature = "mint-policy")]
+pub struct MintPolicyAccount;
+#[cfg(feature = "mint-policy")]
+impl MintPolicyAccount {
+    /// 只有白名单中 (且未被拉黑) 的 mint 才能用于托管；
+    /// 尚未创建的策略 PDA 视为不在白名单中。
+    pub fn check(policy: &AccountView, mint: &AccountView) -> Result<(), ProgramError> {
+        if !policy.owned_by(&crate::ID) {
+            return Err(EscrowError::MintNotAllowed.into());
+        }
+
+        let data = policy.try_borrow()?;
+        let mint_policy = MintPolicy::load(&data)?;
+        let policy_key = Address::create_program_address(
+            &[MINT_POLICY_SEED, mint.address().as_ref(), &mint_policy.bump],
+            &crate::ID,
+        )?;
+        if &policy_key != policy.address() || &mint_policy.mint != mint.address() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        match mint_policy.status {
+            MintPolicy::ALLOWED => Ok(()),
+            MintPolicy::DENIED => Err(EscrowError::MintDenied.into()),
+            _ => Err(EscrowError::InvalidMintPolicy.into()),
         }
-        Ok(())
     }
 }
 
@@ -124,10 +148,158 @@ impl AssociatedTokenAccount {
         mint: &AccountView,
         _token_program: &AccountView,
     ) -> Result<(), ProgramError> {
-        let token_account = TokenAccount::from_account_view(ata)?;
-        if token_account.owner() != owner.address() || token_account.mint() != mint.address() {
+        Ok(validate::token_account(
+            ata,
+            owner.address().as_array(),
+            mint.address().as_array(),
+        )?)
+    }
+
+    /// 只校验代币账户的 mint，不限定所有者 (如推荐人的收款账户)
+    pub fn check_mint(
+        ata: &AccountView,
+        mint: &AccountView,
+        _token_program: &AccountView,
+    ) -> Result<(), ProgramError> {
+        Ok(validate::token_account_mint(
+            ata,
+            mint.address().as_array(),
+        )?)
+    }
+}
+
+// --- 5. 全局配置 (Config PDA) 助手 ---
+pub struct ConfigAccount;
+impl ConfigAccount {
+    /// 校验 Config 由本程序拥有，且地址确为 Config PDA
+    pub fn check(account: &AccountView) -> Result<(), ProgramError> {
+        ProgramAccount::check(account)?;
+
+        if account.address() != &CONFIG_ADDRESS {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Config::load(&account.try_borrow()?)?;
+        Ok(())
+    }
+
+    /// InitConfig 之前 Config PDA 尚不存在 (不属于本程序)，此时返回 `false`。
+    /// 地址仍须是 Config PDA，否则传入任意空账户即可绕过暂停。
+    pub fn is_initialized(account: &AccountView) -> Result<bool, ProgramError> {
+        if account.owned_by(&crate::ID) {
+            Self::check(account)?;
+            return Ok(true);
+        }
+
+        if account.address() != &CONFIG_ADDRESS {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(false)
+    }
+
+    /// 校验签名者为当前管理员 (调用前需先通过 `check`)
+    pub fn check_admin(config: &AccountView, admin: &AccountView) -> Result<(), ProgramError> {
+        SignerAccount::check(admin)?;
+
+        let data = config.try_borrow()?;
+        if &Config::load(&data)?.admin != admin.address() {
+            return Err(EscrowError::UnauthorizedAdmin.into());
+        }
+        Ok(())
+    }
+}
+
+// --- 6. 升级权限 (ProgramData) 助手 ---
+pub struct ProgramData;
+impl ProgramData {
+    pub const LOADER_ID: Address =
+        solana_address::address!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+    /// 校验 `authority` 是本程序的升级权限 (只有它能初始化 Config)
+    pub fn check_upgrade_authority(
+        program_data: &AccountView,
+        authority: &AccountView,
+    ) -> Result<(), ProgramError> {
+        let (program_data_key, _) =
+            Address::find_program_address(&[crate::ID.as_ref()], &Self::LOADER_ID);
+        if &program_data_key != program_data.address() || !program_data.owned_by(&Self::LOADER_ID) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // UpgradeableLoaderState::ProgramData:
+        // tag (u32) | slot (u64) | Option<Address> (1 + 32)
+        let data = program_data.try_borrow()?;
+        if data.len() < 45 || data[0..4] != 3u32.to_le_bytes() {
             return Err(ProgramError::InvalidAccountData);
         }
+        if data[12] != 1 || &data[13..45] != authority.address().as_ref() {
+            return Err(EscrowError::UnauthorizedAdmin.into());
+        }
         Ok(())
     }
 }
+
+// --- 7. 账户布局描述 (顺序 + 签名 / 可写标志) ---
+/// 每条指令固定账户的声明，处理器、CPI 构造器与链下客户端共用同一份。
+pub use escrow_core::AccountSpec;
+
+// --- 8. SPL Token CPI 助手 ---
+/// `signer_seeds` 为空时由 `authority` 直接签名，否则以这些种子推导的 PDA 签名。
+/// 打开 `fuzz` feature 的链下构建同时记入 `cpi_trace`。
+pub struct TokenProgram;
+impl TokenProgram {
+    #[inline(always)]
+    pub fn transfer(
+        from: &AccountView,
+        to: &AccountView,
+        authority: &AccountView,
+        amount: u64,
+        signer_seeds: &[Seed],
+    ) -> ProgramResult {
+        #[cfg(all(feature = "fuzz", not(target_os = "solana")))]
+        crate::cpi_trace::record(
+            crate::cpi_trace::TokenInstruction::Transfer { amount },
+            from,
+            to,
+            authority,
+            signer_seeds,
+        );
+
+        let transfer = Transfer {
+            from,
+            to,
+            authority,
+            amount,
+        };
+        match signer_seeds {
+            [] => transfer.invoke(),
+            seeds => transfer.invoke_signed(&[Signer::from(seeds)]),
+        }
+    }
+
+    #[inline(always)]
+    pub fn close_account(
+        account: &AccountView,
+        destination: &AccountView,
+        authority: &AccountView,
+        signer_seeds: &[Seed],
+    ) -> ProgramResult {
+        #[cfg(all(feature = "fuzz", not(target_os = "solana")))]
+        crate::cpi_trace::record(
+            crate::cpi_trace::TokenInstruction::CloseAccount,
+            account,
+            destination,
+            authority,
+            signer_seeds,
+        );
+
+        let close = CloseAccount {
+            account,
+            destination,
+            authority,
+        };
+        match signer_seeds {
+            [] => close.invoke(),
+            seeds => close.invoke_signed(&[Signer::from(seeds)]),
+        }
+    }
+}
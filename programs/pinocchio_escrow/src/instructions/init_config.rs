@@ -0,0 +1,82 @@
+/*
+由程序的升级权限创建全局 Config PDA，并将其设为初始管理员。
+之后管理员可以暂停 Make / Take，或分两步移交管理权限。
+ */
+
+use escrow_core::validate;
+use pinocchio::{
+    AccountView, ProgramResult,
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{Sysvar, rent::Rent},
+};
+
+use crate::{
+    AccountSpec, CONFIG_ADDRESS, CONFIG_BUMP, CONFIG_SEED, Config, ProgramAccount, ProgramData,
+};
+
+pub struct InitConfig<'a> {
+    pub admin: &'a AccountView,
+    pub config: &'a AccountView,
+    pub program_data: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub bump: u8,
+}
+
+impl<'a> InitConfig<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &3;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 4] = [
+        AccountSpec::writable_signer("admin"),
+        AccountSpec::writable("config"),
+        AccountSpec::readonly("program_data"),
+        AccountSpec::readonly("system_program"),
+    ];
+
+    pub fn try_from(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        validate::account_flags(accounts, &Self::ACCOUNTS)?;
+
+        let [
+            admin,        // 1. Signer (payer, must be the upgrade authority)
+            config,       // 2. Config PDA
+            program_data, // 3. ProgramData of this program
+            system_program,
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        ProgramData::check_upgrade_authority(program_data, admin)?;
+
+        if config.address() != &CONFIG_ADDRESS {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self {
+            admin,
+            config,
+            program_data,
+            system_program,
+            bump: CONFIG_BUMP,
+        })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        let bump_binding = [self.bump];
+        let config_seeds = [Seed::from(CONFIG_SEED), Seed::from(&bump_binding)];
+
+        ProgramAccount::init::<Config>(
+            self.admin,
+            self.config,
+            &config_seeds,
+            Config::LEN,
+            Rent::get()?.try_minimum_balance(Config::LEN)?,
+        )?;
+
+        let mut data = self.config.try_borrow_mut()?;
+        Config::load_mut(&mut data)?.set_inner(*self.admin.address(), bump_binding);
+
+        Ok(())
+    }
+}
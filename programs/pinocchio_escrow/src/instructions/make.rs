@@ -1,96 +1,129 @@
-use pinocchio::{AccountView, Address, ProgramResult, cpi::Seed, error::ProgramError};
-use pinocchio_token::instructions::Transfer;
-
+#[cfg(feature = "mint-policy")]
+use crate::MintPolicyAccount;
 use crate::{
-    AssociatedTokenAccount, ESCROW_SEED, Escrow, MintInterface, ProgramAccount, SignerAccount,
+    AccountSpec, AssociatedTokenAccount, ESCROW_SEED, Escrow, EscrowError, EventEmitter, MAX_BPS,
+    OfferMade, ProgramAccount, TokenProgram,
+};
+use escrow_core::{ArgsReader, RemainingAccounts};
+use escrow_derive::Accounts;
+use pinocchio::{
+    AccountView, Address, ProgramResult,
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{Sysvar, rent::Rent},
 };
 
 /// 初始化托管记录并存储所有交易条款。
 /// 创建金库（一个由 mint_a 拥有的 escrow 的关联代币账户 (ATA)）。
 /// 使用 CPI 调用 SPL-Token 程序，将创建者的 Token A 转移到该金库中。
+#[derive(Accounts)]
 pub struct MakeAccounts<'a> {
+    #[account(signer, writable)]
     pub maker: &'a AccountView,
+    #[account(writable)]
     pub escrow: &'a AccountView,
+    #[account(mint)]
     pub mint_a: &'a AccountView,
+    #[account(mint)]
     pub mint_b: &'a AccountView,
+    #[account(writable, token::mint = mint_a, token::authority = maker)]
     pub maker_ata_a: &'a AccountView,
+    #[account(writable)]
     pub vault: &'a AccountView,
+    #[account(address = pinocchio_system::ID)]
     pub system_program: &'a AccountView,
+    #[account(address = pinocchio_token::ID)]
     pub token_program: &'a AccountView,
+    #[account(address = pinocchio_associated_token_account::ID)]
+    pub associated_token_program: &'a AccountView,
+    pub config: &'a AccountView, // 最后一个固定账户，可选账户都排在它之后
 }
 
-impl<'a> TryFrom<&'a [AccountView]> for MakeAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            maker_ata_a,
-            vault,
-            system_program,
-            token_program,
-            _,
-        ] = accounts
-        else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-        // Basic Accounts Checks
-        SignerAccount::check(maker)?;
-        MintInterface::check(mint_a)?;
-        MintInterface::check(mint_b)?;
-        AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
-
-        // Return the accounts
-        Ok(Self {
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            maker_ata_a,
-            vault,
-            system_program,
-            token_program,
-        })
-    }
-}
-
+/// 版本化编码 (见 `escrow_core::ArgsReader`)：v0 为 `seed | receive | amount`，
+/// 之后每个版本在末尾追加一组字段 (追加后者时前者也必须给出)：
+/// - v1 `referral_bps: u16`：成交时从 `receive` 中分给推荐人的费率，缺省时为 0；
+/// - v2 `rent_recipient | proceeds_recipient: Address`，缺省时均为创建者；
+/// - v3 `settlement: u8`，`Escrow::SETTLE_CLAIM` 时 Token B 先存入收益金库，由创建者 Claim。
 pub struct MakeInstructionData {
     pub seed: u64,
     pub receive: u64,
     pub amount: u64,
+    pub referral_bps: u16,
+    pub rent_recipient: Option<Address>,
+    pub proceeds_recipient: Option<Address>,
+    pub settlement: u8,
+}
+
+impl MakeInstructionData {
+    /// 完整编码 (含全部可选字段) 的长度
+    pub const LEN: usize =
+        size_of::<u64>() * 3 + size_of::<u16>() + size_of::<Address>() * 2 + size_of::<u8>();
+
+    /// 编码为完整形式 (不含判别符)；未指定的租金接收者 / 收款人以 `maker` 填充，与处理器的默认值一致
+    pub fn to_bytes(&self, maker: &Address) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0..8].copy_from_slice(&self.seed.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.receive.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.amount.to_le_bytes());
+        bytes[24..26].copy_from_slice(&self.referral_bps.to_le_bytes());
+        bytes[26..58].copy_from_slice(self.rent_recipient.as_ref().unwrap_or(maker).as_ref());
+        bytes[58..90].copy_from_slice(self.proceeds_recipient.as_ref().unwrap_or(maker).as_ref());
+        bytes[90] = self.settlement;
+        bytes
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len() != size_of::<u64>() * 3 {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-
-        let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
-        let receive = u64::from_le_bytes(data[8..16].try_into().unwrap());
-        let amount = u64::from_le_bytes(data[16..24].try_into().unwrap());
+        let mut args = ArgsReader::new(data);
+        let seed = u64::from_le_bytes(args.read()?);
+        let receive = u64::from_le_bytes(args.read()?);
+        let amount = u64::from_le_bytes(args.read()?);
+        // v1
+        let referral_bps = args.optional()?.map_or(0, u16::from_le_bytes);
+        // v2：两个地址作为一组，不能只给其一
+        let recipients = args.optional::<{ size_of::<Address>() * 2 }>()?;
+        let (rent_recipient, proceeds_recipient) = match recipients {
+            Some(bytes) => (
+                Some(Address::new_from_array(bytes[..32].try_into().unwrap())),
+                Some(Address::new_from_array(bytes[32..].try_into().unwrap())),
+            ),
+            None => (None, None),
+        };
+        // v3
+        let settlement = args
+            .optional()?
+            .map_or(Escrow::SETTLE_PUSH, |[settlement]| settlement);
+        args.finish()?;
 
-        // Instruction Checks
         if amount == 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
+        if referral_bps > MAX_BPS {
+            return Err(EscrowError::InvalidReferralBps.into());
+        }
+        if !matches!(settlement, Escrow::SETTLE_PUSH | Escrow::SETTLE_CLAIM) {
+            return Err(EscrowError::InvalidSettlement.into());
+        }
 
         Ok(Self {
             seed,
             receive,
             amount,
+            referral_bps,
+            rent_recipient,
+            proceeds_recipient,
+            settlement,
         })
     }
 }
 
 pub struct Make<'a> {
     pub accounts: MakeAccounts<'a>,
+    pub emitter: EventEmitter<'a>,
+    pub proceeds_vault: Option<&'a AccountView>,
     pub instruction_data: MakeInstructionData,
     pub bump: u8,
 }
@@ -99,7 +132,20 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
     type Error = ProgramError;
 
     fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
-        let accounts = MakeAccounts::try_from(accounts)?;
+        let (accounts, remaining) = MakeAccounts::try_accounts(accounts)?;
+        let mut remaining = RemainingAccounts::new(remaining, crate::ID.as_array());
+
+        // Mint allowlist / denylist, policy PDAs trail the fixed set
+        #[cfg(feature = "mint-policy")]
+        {
+            MintPolicyAccount::check(remaining.required()?, accounts.mint_a)?;
+            MintPolicyAccount::check(remaining.required()?, accounts.mint_b)?;
+        }
+
+        // Self-CPI event accounts (cpi-events), then the claim-mode proceeds vault
+        let emitter = EventEmitter::parse(&mut remaining)?;
+        let proceeds_vault = remaining.optional();
+
         let instruction_data = MakeInstructionData::try_from(data)?;
 
         // Initialize the Accounts needed
@@ -126,7 +172,7 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
             accounts.escrow,
             &escrow_seeds,
             Escrow::LEN,
-            2_000_000,
+            Rent::get()?.try_minimum_balance(Escrow::LEN)?,
         )?;
 
         // Initialize the vault
@@ -139,8 +185,24 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
             accounts.token_program,
         )?;
 
+        // Initialize the proceeds vault (escrow's ATA for mint_b) for claim-mode offers
+        if instruction_data.settlement == Escrow::SETTLE_CLAIM {
+            let proceeds_vault = proceeds_vault.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+            AssociatedTokenAccount::init(
+                proceeds_vault,
+                accounts.mint_b,
+                accounts.maker,
+                accounts.escrow,
+                accounts.system_program,
+                accounts.token_program,
+            )?;
+        }
+
         Ok(Self {
             accounts,
+            emitter,
+            proceeds_vault,
             instruction_data,
             bump,
         })
@@ -149,6 +211,11 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
 
 impl<'a> Make<'a> {
     pub const DISCRIMINATOR: &'a u8 = &0;
+    /// 固定账户，由 `MakeAccounts` 的字段与 `#[account(signer, writable)]` 声明生成
+    pub const ACCOUNTS: [AccountSpec; 10] = MakeAccounts::ACCOUNTS;
+
+    /// Config 在账户列表中的位置 (供 `process_instruction` 检查暂停状态)
+    pub const CONFIG_INDEX: usize = AccountSpec::index_of(&Self::ACCOUNTS, "config");
 
     pub fn process(&mut self) -> ProgramResult {
         // Populate the escrow account
@@ -157,21 +224,49 @@ impl<'a> Make<'a> {
 
         escrow.set_inner(
             self.instruction_data.seed,
-            self.accounts.maker.address().clone(),
-            self.accounts.mint_a.address().clone(),
-            self.accounts.mint_b.address().clone(),
+            *self.accounts.maker.address(),
+            *self.accounts.mint_a.address(),
+            *self.accounts.mint_b.address(),
             self.instruction_data.receive,
             [self.bump],
         );
+        escrow.set_referral_bps(self.instruction_data.referral_bps);
+        escrow.set_rent_recipient(
+            self.instruction_data
+                .rent_recipient
+                .unwrap_or_else(|| *self.accounts.maker.address()),
+        );
+        escrow.set_proceeds_recipient(
+            self.instruction_data
+                .proceeds_recipient
+                .unwrap_or_else(|| *self.accounts.maker.address()),
+        );
+        escrow.set_settlement(self.instruction_data.settlement);
+        escrow.set_status(Escrow::STATUS_OPEN);
 
         // Transfer tokens to vault
-        Transfer {
-            from: self.accounts.maker_ata_a,
-            to: self.accounts.vault,
-            authority: self.accounts.maker,
-            amount: self.instruction_data.amount,
-        }
-        .invoke()?;
+        TokenProgram::transfer(
+            self.accounts.maker_ata_a,
+            self.accounts.vault,
+            self.accounts.maker,
+            self.instruction_data.amount,
+            &[],
+        )?;
+
+        self.emitter.emit(
+            &OfferMade {
+                escrow: *self.accounts.escrow.address(),
+                maker: *self.accounts.maker.address(),
+                mint_a: *self.accounts.mint_a.address(),
+                mint_b: *self.accounts.mint_b.address(),
+                seed: self.instruction_data.seed,
+                amount: self.instruction_data.amount,
+                receive: self.instruction_data.receive,
+                referral_bps: self.instruction_data.referral_bps,
+                settlement: self.instruction_data.settlement,
+            }
+            .to_bytes(),
+        )?;
 
         Ok(())
     }
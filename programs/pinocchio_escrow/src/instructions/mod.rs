@@ -1,9 +1,25 @@
+pub mod accept_admin;
+pub mod claim;
+pub mod emit_event;
 pub mod helpers;
+pub mod init_config;
 pub mod make;
+pub mod quote;
 pub mod refund;
+pub mod set_mint_policy;
+pub mod set_pause;
 pub mod take;
+pub mod transfer_admin;
 
+pub use accept_admin::*;
+pub use claim::*;
+pub use emit_event::*;
 pub use helpers::*;
+pub use init_config::*;
 pub use make::*;
+pub use quote::*;
 pub use refund::*;
+pub use set_mint_policy::*;
+pub use set_pause::*;
 pub use take::*;
+pub use transfer_admin::*;
@@ -0,0 +1,69 @@
+/*
+只读的报价查询：加载 Escrow 与金库，不做任何写入，
+通过 return data 返回 Take 将会结算的数量，前端可直接模拟 (simulate) 该指令。
+报价只能整单成交、没有有效期，因此返回值中没有剩余数量与过期状态 (布局见 `Quote::to_bytes`)。
+
+指令数据可选 1 字节：1 表示按带推荐人的情况计算推荐费。
+ */
+
+use pinocchio::{AccountView, ProgramResult, cpi::set_return_data, error::ProgramError};
+use pinocchio_token::state::TokenAccount;
+
+use crate::{AccountSpec, EscrowVersion, ProgramAccount, Quote};
+
+pub struct QuoteOffer<'a> {
+    pub escrow: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub with_referrer: bool,
+}
+
+impl<'a> QuoteOffer<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &10;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 2] = [
+        AccountSpec::readonly("escrow"),
+        AccountSpec::readonly("vault"),
+    ];
+
+    pub fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, ProgramError> {
+        let [escrow, vault] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let with_referrer = match data {
+            [] | [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self {
+            escrow,
+            vault,
+            with_referrer,
+        })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        let data = self.escrow.try_borrow()?;
+        let escrow = EscrowVersion::load(&data)?;
+
+        // 已成交的报价金库已关闭，不再读取
+        let vault_amount = if escrow.is_filled() {
+            0
+        } else {
+            let vault = TokenAccount::from_account_view(self.vault)?;
+            if vault.owner() != self.escrow.address() || vault.mint() != escrow.mint_a() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            vault.amount()
+        };
+
+        let quote = Quote::new(&escrow, vault_amount, self.with_referrer);
+        set_return_data(&quote.to_bytes());
+
+        Ok(())
+    }
+}
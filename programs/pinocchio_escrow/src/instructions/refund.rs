@@ -1,115 +1,230 @@
 /*
 refund 指令允许创建者取消一个未完成的报价：
 
-关闭托管 PDA，并将其租金 lamports 返还给创建者。
+关闭托管 PDA，并将其租金 lamports 返还给租金接收者 (默认为创建者)。
 
-将代币 A 的全部余额从保险库转回创建者，然后关闭保险库账户。
+将代币 A 的全部余额从保险库转回创建者，然后关闭保险库账户 (租金同样返还给租金接收者)。
+
+Claim 模式的报价还需在末尾传入收益金库、mint_b、收益接收者及其 mint_b ATA：
+任何人都能向收益金库转入代币，关闭前先把余额转给收益接收者，否则 CloseAccount 会失败。
+
+旧版布局 (`LegacyEscrow`) 的托管同样可以取回，租金退回创建者。
  */
 
-use std::slice;
+use escrow_core::{ArgsReader, RemainingAccounts};
+use escrow_derive::Accounts;
+use pinocchio::{AccountView, ProgramResult, cpi::Seed, error::ProgramError};
+use pinocchio_token::state::TokenAccount;
 
-use pinocchio::{
-    AccountView, ProgramResult,
-    cpi::{Seed, Signer},
-    error::ProgramError,
+use crate::{
+    AccountSpec, AssociatedTokenAccount, ESCROW_SEED, Escrow, EscrowError, EscrowVersion,
+    EventEmitter, OfferRefunded, ProgramAccount, SettledAmounts, TokenProgram,
 };
-use pinocchio_token::{
-    instructions::{CloseAccount, Transfer},
-    state::TokenAccount,
-};
-
-use crate::{AssociatedTokenAccount, ESCROW_SEED, Escrow, ProgramAccount};
 
-pub struct Refund<'a> {
+#[derive(Accounts)]
+pub struct RefundAccounts<'a> {
+    // 托管 PDA 由 maker 地址推导，不签名任何人都能替 maker 取消报价
+    #[account(signer, writable)]
     pub maker: &'a AccountView,
+    #[account(
+        writable,
+        owner = crate::ID,
+        seeds = [ESCROW_SEED, maker.address().as_ref(), &Escrow::seed_bytes_of(escrow)?],
+        bump = Escrow::bump_of(escrow)?,
+    )]
     pub escrow: &'a AccountView,
+    #[account(mint, address = Escrow::mint_a_of(escrow)?)]
     pub mint_a: &'a AccountView,
+    #[account(writable, associated_token::mint = mint_a, associated_token::authority = escrow)]
     pub vault: &'a AccountView,
+    #[account(writable)]
     pub maker_ata_a: &'a AccountView,
+    #[account(address = pinocchio_associated_token_account::ID)]
     pub associated_token_program: &'a AccountView,
+    #[account(address = pinocchio_token::ID)]
     pub token_program: &'a AccountView,
+    #[account(address = pinocchio_system::ID)]
     pub system_program: &'a AccountView,
+    #[account(writable)]
+    pub rent_recipient: &'a AccountView,
+}
+
+/// 版本化编码 (见 `escrow_core::ArgsReader`)：v0 为空 (只有判别符)。
+/// 现在就拒绝多余字节，以后追加的字段在旧程序上会明确失败而不是被忽略。
+pub struct RefundInstructionData;
+
+impl<'a> TryFrom<&'a [u8]> for RefundInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        ArgsReader::new(data).finish()?;
+        Ok(Self)
+    }
+}
+
+/// Claim 模式追加的账户，顺序即传入顺序
+pub struct RefundProceedsAccounts<'a> {
+    pub proceeds_vault: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub proceeds_recipient: &'a AccountView,
+    pub proceeds_ata_b: &'a AccountView,
+}
+
+pub struct Refund<'a> {
+    pub accounts: RefundAccounts<'a>,
+    pub emitter: EventEmitter<'a>,
+    pub proceeds: Option<RefundProceedsAccounts<'a>>,
 }
 
 impl<'a> Refund<'a> {
     pub const DISCRIMINATOR: &'a u8 = &2;
-    pub fn try_from(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
-        // 使用简单的切片模式匹配来获取账户，性能最优
-        let [
-            maker,       // 1. Signer
-            escrow,      // 2. Escrow PDA
-            mint_a,      // 3. Mint A (Anchor 里的第三个账户)
-            vault,       // 4. Vault (Token Account)
-            maker_ata_a, // 5. Maker ATA
-            associated_token_program,
-            token_program,  // 6. Token Program
-            system_program, // 7. System Program
-        ] = accounts
-        else {
-            return Err(ProgramError::NotEnoughAccountKeys);
+
+    /// 固定账户，由 `RefundAccounts` 的字段与 `#[account(signer, writable)]` 声明生成
+    pub const ACCOUNTS: [AccountSpec; 9] = RefundAccounts::ACCOUNTS;
+
+    pub fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, ProgramError> {
+        RefundInstructionData::try_from(data)?;
+
+        let (accounts, remaining) = RefundAccounts::try_accounts(accounts)?;
+        let mut remaining = RemainingAccounts::new(remaining, crate::ID.as_array());
+
+        // Event accounts (cpi-events), then the proceeds accounts (claim mode)
+        let emitter = EventEmitter::parse(&mut remaining)?;
+        let proceeds = match remaining.optional() {
+            Some(proceeds_vault) => Some(RefundProceedsAccounts {
+                proceeds_vault,
+                mint_b: remaining.required()?,
+                proceeds_recipient: remaining.required()?,
+                proceeds_ata_b: remaining.required()?,
+            }),
+            None => None,
         };
 
         Ok(Self {
-            maker,
-            escrow,
-            mint_a,
-            vault,
-            maker_ata_a,
-            associated_token_program,
-            token_program,
-            system_program,
+            accounts,
+            emitter,
+            proceeds,
         })
     }
 
     pub fn process(&self) -> ProgramResult {
         AssociatedTokenAccount::init_if_needed(
-            self.maker_ata_a,
-            self.mint_a,
-            self.maker,
-            self.maker,
-            self.system_program,
-            self.token_program,
+            self.accounts.maker_ata_a,
+            self.accounts.mint_a,
+            self.accounts.maker,
+            self.accounts.maker,
+            self.accounts.system_program,
+            self.accounts.token_program,
         )?;
 
-        // 1. 获取 Escrow 数据视图 (零拷贝)
-        let data = self.escrow.try_borrow()?;
-        let escrow_state = Escrow::load(&data)?;
+        // 1. 获取 Escrow 数据视图 (零拷贝，兼容旧版布局)
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow_state = EscrowVersion::load(&data)?;
+
+        if escrow_state.is_filled() {
+            return Err(EscrowError::OfferAlreadyFilled.into());
+        }
+        if escrow_state.rent_recipient() != self.accounts.rent_recipient.address() {
+            return Err(EscrowError::InvalidRentRecipient.into());
+        }
 
         // 2. 构造 PDA 签名
-        let seed_bytes = escrow_state.seed.to_le_bytes();
         let seeds = [
             Seed::from(ESCROW_SEED),
-            Seed::from(self.maker.address().as_ref()),
-            Seed::from(&seed_bytes),
-            Seed::from(&escrow_state.bump),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(escrow_state.seed()),
+            Seed::from(escrow_state.bump()),
         ];
-        let signer = Signer::from(&seeds);
 
         // 检查 amount 之前确保 vault 数据有效
-        let amount = TokenAccount::from_account_view(self.vault)?.amount();
+        let amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
 
         if amount > 0 {
             // 执行转账: Vault (from) -> Maker ATA (to)
             // 必须确认识别到的 vault 账户的所有者是 escrow PDA
-            Transfer {
-                from: self.vault,
-                to: self.maker_ata_a,
-                authority: self.escrow, // 这里必须是 PDA
+            TokenProgram::transfer(
+                self.accounts.vault,
+                self.accounts.maker_ata_a,
+                self.accounts.escrow, // 这里必须是 PDA
                 amount,
-            }
-            .invoke_signed(slice::from_ref(&signer))?;
+                &seeds,
+            )?;
         }
 
         // 关闭 Vault 账户
-        CloseAccount {
-            account: self.vault,
-            destination: self.maker,
-            authority: self.escrow,
+        TokenProgram::close_account(
+            self.accounts.vault,
+            self.accounts.rent_recipient,
+            self.accounts.escrow,
+            &seeds,
+        )?;
+
+        // Claim 模式：转出收益金库中被转入的代币，再关闭它
+        if escrow_state.is_claim_mode() {
+            let proceeds = self
+                .proceeds
+                .as_ref()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let balance = {
+                let vault = TokenAccount::from_account_view(proceeds.proceeds_vault)?;
+                if vault.owner() != self.accounts.escrow.address()
+                    || vault.mint() != escrow_state.mint_b()
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                vault.amount()
+            };
+
+            if balance > 0 {
+                if proceeds.mint_b.address() != escrow_state.mint_b() {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                if proceeds.proceeds_recipient.address() != escrow_state.proceeds_recipient() {
+                    return Err(EscrowError::InvalidProceedsRecipient.into());
+                }
+                AssociatedTokenAccount::init_if_needed(
+                    proceeds.proceeds_ata_b,
+                    proceeds.mint_b,
+                    self.accounts.maker,
+                    proceeds.proceeds_recipient,
+                    self.accounts.system_program,
+                    self.accounts.token_program,
+                )?;
+                TokenProgram::transfer(
+                    proceeds.proceeds_vault,
+                    proceeds.proceeds_ata_b,
+                    self.accounts.escrow,
+                    balance,
+                    &seeds,
+                )?;
+            }
+
+            TokenProgram::close_account(
+                proceeds.proceeds_vault,
+                self.accounts.rent_recipient,
+                self.accounts.escrow,
+                &seeds,
+            )?;
         }
-        .invoke_signed(&[signer])?;
 
         drop(data); // 必须在 close escrow 前释放
-        ProgramAccount::close(self.escrow, self.maker)?;
+        ProgramAccount::close(self.accounts.escrow, self.accounts.rent_recipient)?;
+
+        self.emitter.emit(
+            &OfferRefunded {
+                escrow: *self.accounts.escrow.address(),
+                maker: *self.accounts.maker.address(),
+                amount_a: amount,
+            }
+            .to_bytes(),
+        )?;
+
+        SettledAmounts {
+            amount_out: amount,
+            amount_in: 0,
+            fee: 0,
+        }
+        .set_return_data();
 
         Ok(())
     }
@@ -0,0 +1,107 @@
+/*
+管理员将某个 mint 加入白名单或黑名单 (需启用 `mint-policy` feature)。
+策略 PDA 不存在时由管理员出资创建，已存在时只更新状态。
+ */
+
+use escrow_core::validate;
+use pinocchio::{
+    AccountView, Address, ProgramResult,
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{Sysvar, rent::Rent},
+};
+
+use crate::{
+    AccountSpec, ConfigAccount, EscrowError, MINT_POLICY_SEED, MintInterface, MintPolicy,
+    ProgramAccount,
+};
+
+pub struct SetMintPolicy<'a> {
+    pub admin: &'a AccountView,
+    pub config: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub mint_policy: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub status: u8,
+    pub bump: u8,
+}
+
+impl<'a> SetMintPolicy<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &7;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 5] = [
+        AccountSpec::writable_signer("admin"),
+        AccountSpec::readonly("config"),
+        AccountSpec::readonly("mint"),
+        AccountSpec::writable("mint_policy"),
+        AccountSpec::readonly("system_program"),
+    ];
+
+    pub fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, ProgramError> {
+        validate::account_flags(accounts, &Self::ACCOUNTS)?;
+
+        let [
+            admin,       // 1. Signer (payer)
+            config,      // 2. Config PDA
+            mint,        // 3. Mint the policy applies to
+            mint_policy, // 4. MintPolicy PDA
+            system_program,
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let [status] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if !matches!(*status, MintPolicy::ALLOWED | MintPolicy::DENIED) {
+            return Err(EscrowError::InvalidMintPolicy.into());
+        }
+
+        ConfigAccount::check(config)?;
+        ConfigAccount::check_admin(config, admin)?;
+        MintInterface::check(mint)?;
+
+        let (policy_key, bump) =
+            Address::find_program_address(&[MINT_POLICY_SEED, mint.address().as_ref()], &crate::ID);
+        if &policy_key != mint_policy.address() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self {
+            admin,
+            config,
+            mint,
+            mint_policy,
+            system_program,
+            status: *status,
+            bump,
+        })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        let bump_binding = [self.bump];
+
+        if !self.mint_policy.owned_by(&crate::ID) {
+            let policy_seeds = [
+                Seed::from(MINT_POLICY_SEED),
+                Seed::from(self.mint.address().as_ref()),
+                Seed::from(&bump_binding),
+            ];
+
+            ProgramAccount::init::<MintPolicy>(
+                self.admin,
+                self.mint_policy,
+                &policy_seeds,
+                MintPolicy::LEN,
+                Rent::get()?.try_minimum_balance(MintPolicy::LEN)?,
+            )?;
+        }
+
+        let mut data = self.mint_policy.try_borrow_mut()?;
+        MintPolicy::load_mut(&mut data)?.set_inner(*self.mint.address(), self.status, bump_binding);
+
+        Ok(())
+    }
+}
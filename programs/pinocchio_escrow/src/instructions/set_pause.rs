@@ -0,0 +1,56 @@
+/*
+管理员设置暂停标志 (`Config::PAUSE_MAKE` / `Config::PAUSE_TAKE`)。
+传入 0 即恢复全部指令；Refund 不可暂停。
+ */
+
+use escrow_core::validate;
+use pinocchio::{AccountView, ProgramResult, error::ProgramError};
+
+use crate::{AccountSpec, Config, ConfigAccount, EscrowError};
+
+pub struct SetPause<'a> {
+    pub admin: &'a AccountView,
+    pub config: &'a AccountView,
+    pub paused: u8,
+}
+
+impl<'a> SetPause<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &4;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 2] = [
+        AccountSpec::readonly_signer("admin"),
+        AccountSpec::writable("config"),
+    ];
+
+    pub fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, ProgramError> {
+        validate::account_flags(accounts, &Self::ACCOUNTS)?;
+
+        let [admin, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let [paused] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if paused & !Config::PAUSABLE != 0 {
+            return Err(EscrowError::InvalidPauseFlags.into());
+        }
+
+        ConfigAccount::check(config)?;
+        ConfigAccount::check_admin(config, admin)?;
+
+        Ok(Self {
+            admin,
+            config,
+            paused: *paused,
+        })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.config.try_borrow_mut()?;
+        Config::load_mut(&mut data)?.set_paused(self.paused);
+
+        Ok(())
+    }
+}
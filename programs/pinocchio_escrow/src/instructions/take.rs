@@ -1,97 +1,146 @@
-use std::slice;
-
-use pinocchio::{
-    AccountView, Address, ProgramResult,
-    cpi::{Seed, Signer},
-    error::ProgramError,
-};
-use pinocchio_token::{
-    instructions::{CloseAccount, Transfer},
-    state::TokenAccount,
-};
+use escrow_core::{ArgsReader, RemainingAccounts};
+use escrow_derive::Accounts;
+use pinocchio::{AccountView, ProgramResult, cpi::Seed, error::ProgramError};
+use pinocchio_token::state::TokenAccount;
 
 use crate::{
-    AssociatedTokenAccount, ESCROW_SEED, Escrow, MintInterface, ProgramAccount, SignerAccount,
+    AccountSpec, AssociatedTokenAccount, ESCROW_SEED, Escrow, EscrowError, EscrowVersion,
+    EventEmitter, OfferTaken, ProgramAccount, Quote, TokenProgram,
 };
 
 /*
-关闭托管记录，将其租金 lamports 返还给创建者。
+关闭托管记录，将其租金 lamports 返还给租金接收者 (默认为创建者)。
+
+将 Token A 从保管库转移到接受者，然后关闭保管库 (租金同样返还给租金接收者)。
+
+将约定数量的 Token B 从接受者转移到收款人 (默认为创建者) 的 ATA。
+若在固定账户之后追加了推荐人的 Token B 账户，`receive` 中按 `referral_bps` 计算的推荐费付给推荐人，
+收款人得到其余部分；推荐人不能是创建者、收款人或接受者本人。
+
+旧版布局 (`LegacyEscrow`) 的报价同样可以成交：没有推荐费，租金与 Token B 都归创建者。
 
-将 Token A 从保管库转移到接受者，然后关闭保管库。
+Claim 模式下 Token B 改为存入收益金库 (maker_ata_b 位置传入)，托管记录保留到创建者 Claim 为止。
 
-将约定数量的 Token B 从接受者转移到创建者。
+两个 mint 必须与托管记录一致，金库必须是托管 PDA 的 mint_a ATA，
+否则接受者可以用自己发行的代币支付 Token B 却取走金库中的 Token A。
 */
 
+#[derive(Accounts)]
 pub struct TakeAccounts<'a> {
+    #[account(signer, writable)]
     pub taker: &'a AccountView,
     pub maker: &'a AccountView,
+    #[account(
+        writable,
+        owner = crate::ID,
+        seeds = [ESCROW_SEED, maker.address().as_ref(), &Escrow::seed_bytes_of(escrow)?],
+        bump = Escrow::bump_of(escrow)?,
+    )]
     pub escrow: &'a AccountView,
+    #[account(mint, address = Escrow::mint_a_of(escrow)?)]
     pub mint_a: &'a AccountView,
+    #[account(mint, address = Escrow::mint_b_of(escrow)?)]
     pub mint_b: &'a AccountView,
+    #[account(writable, associated_token::mint = mint_a, associated_token::authority = escrow)]
     pub vault: &'a AccountView,
+    #[account(writable)]
     pub taker_ata_a: &'a AccountView,
+    #[account(writable, token::mint = mint_b, token::authority = taker)]
     pub taker_ata_b: &'a AccountView,
-    pub maker_ata_b: &'a AccountView,
+    #[account(writable)]
+    pub maker_ata_b: &'a AccountView, // ATA of the proceeds recipient, or the proceeds vault
+    #[account(address = pinocchio_system::ID)]
     pub system_program: &'a AccountView,
+    #[account(address = pinocchio_token::ID)]
     pub token_program: &'a AccountView,
+    #[account(address = pinocchio_associated_token_account::ID)]
+    pub associated_token_program: &'a AccountView,
+    #[account(writable)]
+    pub rent_recipient: &'a AccountView,
+    pub proceeds_recipient: &'a AccountView,
+    pub config: &'a AccountView, // 最后一个固定账户，可选账户都排在它之后
+}
+
+/// 版本化编码 (见 `escrow_core::ArgsReader`)，v0 为空 (只有判别符)：
+/// - v1 `max_amount_in | min_amount_out: u64`：接受者最多支付的 Token B (含推荐费)
+///   与至少收到的 Token A；报价在签名后被撤销、以同一 seed 重建为不同条款时成交失败。
+///   缺省时不做限制。
+pub struct TakeInstructionData {
+    pub max_amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+impl TakeInstructionData {
+    /// 完整编码的长度
+    pub const LEN: usize = size_of::<u64>() * 2;
+
+    /// 不限制成交条款，与 v0 (空数据) 等价
+    pub const UNCHECKED: Self = Self {
+        max_amount_in: u64::MAX,
+        min_amount_out: 0,
+    };
+
+    /// 编码为完整形式 (不含判别符)
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0..8].copy_from_slice(&self.max_amount_in.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.min_amount_out.to_le_bytes());
+        bytes
+    }
 }
 
-impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
+impl<'a> TryFrom<&'a [u8]> for TakeInstructionData {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [
-            taker,
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            vault,
-            taker_ata_a,
-            taker_ata_b,
-            maker_ata_b,
-            system_program,
-            token_program,
-            _,
-        ] = accounts
-        else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-        // Basic Accounts Checks
-        SignerAccount::check(taker)?;
-        ProgramAccount::check(escrow)?;
-        MintInterface::check(mint_a)?;
-        MintInterface::check(mint_b)?;
-        AssociatedTokenAccount::check(taker_ata_b, taker, mint_b, token_program)?;
-        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
-
-        // Return the accounts
-        Ok(Self {
-            taker,
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            taker_ata_a,
-            taker_ata_b,
-            maker_ata_b,
-            vault,
-            system_program,
-            token_program,
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut args = ArgsReader::new(data);
+        // v1：两个限制作为一组
+        let limits = args.optional::<{ Self::LEN }>()?;
+        args.finish()?;
+
+        Ok(match limits {
+            Some(bytes) => Self {
+                max_amount_in: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+                min_amount_out: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            },
+            None => Self::UNCHECKED,
         })
     }
 }
 
 pub struct Take<'a> {
     pub accounts: TakeAccounts<'a>,
+    pub emitter: EventEmitter<'a>,
+    pub referrer_ata_b: Option<&'a AccountView>,
+    pub instruction_data: TakeInstructionData,
 }
 
-impl<'a> TryFrom<&'a [AccountView]> for Take<'a> {
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Take<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let accounts = TakeAccounts::try_from(accounts)?;
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let (accounts, remaining) = TakeAccounts::try_accounts(accounts)?;
+        let mut remaining = RemainingAccounts::new(remaining, crate::ID.as_array());
+
+        // Self-CPI event accounts (cpi-events), then the optional referrer account
+        let emitter = EventEmitter::parse(&mut remaining)?;
+        let referrer_ata_b = remaining.optional();
+        if let Some(referrer_ata_b) = referrer_ata_b {
+            AssociatedTokenAccount::check_mint(
+                referrer_ata_b,
+                accounts.mint_b,
+                accounts.token_program,
+            )?;
+
+            // 推荐费不能回到交易双方手中 (收款人账户在 `process` 中与托管记录比对)
+            let referrer = TokenAccount::from_account_view(referrer_ata_b)?;
+            if referrer.owner() == accounts.taker.address()
+                || referrer.owner() == accounts.maker.address()
+                || referrer.owner() == accounts.proceeds_recipient.address()
+            {
+                return Err(EscrowError::InvalidReferrer.into());
+            }
+        }
 
         // Initialize necessary accounts
         AssociatedTokenAccount::init_if_needed(
@@ -103,80 +152,142 @@ impl<'a> TryFrom<&'a [AccountView]> for Take<'a> {
             accounts.token_program,
         )?;
 
-        AssociatedTokenAccount::init_if_needed(
-            accounts.maker_ata_b,
-            accounts.mint_b,
-            accounts.taker,
-            accounts.maker,
-            accounts.system_program,
-            accounts.token_program,
-        )?;
+        // Claim-mode offers pay into the proceeds vault created at Make
+        if EscrowVersion::load(&accounts.escrow.try_borrow()?)?.is_claim_mode() {
+            AssociatedTokenAccount::check(
+                accounts.maker_ata_b,
+                accounts.escrow,
+                accounts.mint_b,
+                accounts.token_program,
+            )?;
+        } else {
+            AssociatedTokenAccount::init_if_needed(
+                accounts.maker_ata_b,
+                accounts.mint_b,
+                accounts.taker,
+                accounts.proceeds_recipient,
+                accounts.system_program,
+                accounts.token_program,
+            )?;
+        }
 
-        Ok(Self { accounts })
+        let instruction_data = TakeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            emitter,
+            referrer_ata_b,
+            instruction_data,
+        })
     }
 }
 
 impl<'a> Take<'a> {
     pub const DISCRIMINATOR: &'a u8 = &1;
+    /// 固定账户，由 `TakeAccounts` 的字段与 `#[account(signer, writable)]` 声明生成
+    pub const ACCOUNTS: [AccountSpec; 15] = TakeAccounts::ACCOUNTS;
+
+    /// Config 在账户列表中的位置 (供 `process_instruction` 检查暂停状态)
+    pub const CONFIG_INDEX: usize = AccountSpec::index_of(&Self::ACCOUNTS, "config");
 
     pub fn process(&mut self) -> ProgramResult {
         let data = self.accounts.escrow.try_borrow()?;
-        let escrow = Escrow::load(&data)?;
-
-        // Check if the escrow is valid
-        let escrow_key = Address::create_program_address(
-            &[
-                ESCROW_SEED,
-                self.accounts.maker.address().as_ref(),
-                &escrow.seed.to_le_bytes(),
-                &escrow.bump,
-            ],
-            &crate::ID,
-        )?;
-        if &escrow_key != self.accounts.escrow.address() {
-            return Err(ProgramError::InvalidAccountOwner);
+        let escrow = EscrowVersion::load(&data)?;
+
+        if escrow.is_filled() {
+            return Err(EscrowError::OfferAlreadyFilled.into());
+        }
+
+        // Rent and proceeds must go where the maker asked
+        if escrow.rent_recipient() != self.accounts.rent_recipient.address() {
+            return Err(EscrowError::InvalidRentRecipient.into());
+        }
+        if escrow.proceeds_recipient() != self.accounts.proceeds_recipient.address() {
+            return Err(EscrowError::InvalidProceedsRecipient.into());
         }
 
-        let seed_bytes = escrow.seed.to_le_bytes();
         let escrow_seeds = [
             Seed::from(ESCROW_SEED),
             Seed::from(self.accounts.maker.address().as_ref()),
-            Seed::from(&seed_bytes),
-            Seed::from(&escrow.bump),
+            Seed::from(escrow.seed()),
+            Seed::from(escrow.bump()),
         ];
-        let signer = Signer::from(&escrow_seeds);
 
         let amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
 
-        // Transfer from the Vault to the Taker
-        Transfer {
-            from: self.accounts.vault,
-            to: self.accounts.taker_ata_a,
-            authority: self.accounts.escrow,
-            amount,
+        // Price the fill with the same code as the Quote instruction
+        let quote = Quote::new(&escrow, amount, self.referrer_ata_b.is_some());
+        if quote.amount_in > self.instruction_data.max_amount_in
+            || quote.amount_out < self.instruction_data.min_amount_out
+        {
+            return Err(EscrowError::SlippageExceeded.into());
         }
-        .invoke_signed(slice::from_ref(&signer))?;
+
+        // Transfer from the Vault to the Taker
+        TokenProgram::transfer(
+            self.accounts.vault,
+            self.accounts.taker_ata_a,
+            self.accounts.escrow,
+            quote.amount_out,
+            &escrow_seeds,
+        )?;
 
         // Close the Vault
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
-        }
-        .invoke_signed(slice::from_ref(&signer))?;
-
-        // Transfer from the Taker to the Maker
-        Transfer {
-            from: self.accounts.taker_ata_b,
-            to: self.accounts.maker_ata_b,
-            authority: self.accounts.taker,
-            amount: escrow.receive,
+        TokenProgram::close_account(
+            self.accounts.vault,
+            self.accounts.rent_recipient,
+            self.accounts.escrow,
+            &escrow_seeds,
+        )?;
+
+        // Transfer from the Taker to the proceeds recipient (or the proceeds vault)
+        TokenProgram::transfer(
+            self.accounts.taker_ata_b,
+            self.accounts.maker_ata_b,
+            self.accounts.taker,
+            quote.maker_amount(),
+            &[],
+        )?;
+
+        // Transfer from the Taker to the Referrer
+        if let Some(referrer_ata_b) = self.referrer_ata_b
+            && quote.fee > 0
+        {
+            TokenProgram::transfer(
+                self.accounts.taker_ata_b,
+                referrer_ata_b,
+                self.accounts.taker,
+                quote.fee,
+                &[],
+            )?;
         }
-        .invoke()?;
 
-        // Close the Escrow
+        self.emitter.emit(
+            &OfferTaken {
+                escrow: *self.accounts.escrow.address(),
+                maker: *self.accounts.maker.address(),
+                taker: *self.accounts.taker.address(),
+                amount_a: quote.amount_out,
+                amount_b: quote.maker_amount(),
+                fee: quote.fee,
+            }
+            .to_bytes(),
+        )?;
+
+        // Every CPI clears return data, so set it after the last one
+        quote.settled().set_return_data();
+
+        let claim_mode = escrow.is_claim_mode();
         drop(data);
-        ProgramAccount::close(self.accounts.escrow, self.accounts.taker)?;
+
+        if claim_mode {
+            // Keep the Escrow until the maker claims the proceeds
+            let mut data = self.accounts.escrow.try_borrow_mut()?;
+            Escrow::load_mut(&mut data)?.set_status(Escrow::STATUS_FILLED);
+        } else {
+            // Close the Escrow
+            ProgramAccount::close(self.accounts.escrow, self.accounts.rent_recipient)?;
+        }
 
         Ok(())
     }
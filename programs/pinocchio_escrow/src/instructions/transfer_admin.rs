@@ -0,0 +1,53 @@
+/*
+两步移交管理员权限的第一步：当前管理员提名新的管理员。
+新管理员需调用 AcceptAdmin 才会生效；提名全零地址即撤销提名。
+ */
+
+use escrow_core::validate;
+use pinocchio::{AccountView, Address, ProgramResult, error::ProgramError};
+
+use crate::{AccountSpec, Config, ConfigAccount};
+
+pub struct TransferAdmin<'a> {
+    pub admin: &'a AccountView,
+    pub config: &'a AccountView,
+    pub new_admin: Address,
+}
+
+impl<'a> TransferAdmin<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &5;
+
+    /// 账户顺序与 `try_from` 一致
+    pub const ACCOUNTS: [AccountSpec; 2] = [
+        AccountSpec::readonly_signer("admin"),
+        AccountSpec::writable("config"),
+    ];
+
+    pub fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, ProgramError> {
+        validate::account_flags(accounts, &Self::ACCOUNTS)?;
+
+        let [admin, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let new_admin: [u8; 32] = data
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        ConfigAccount::check(config)?;
+        ConfigAccount::check_admin(config, admin)?;
+
+        Ok(Self {
+            admin,
+            config,
+            new_admin: Address::new_from_array(new_admin),
+        })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.config.try_borrow_mut()?;
+        Config::load_mut(&mut data)?.set_pending_admin(self.new_admin);
+
+        Ok(())
+    }
+}
@@ -1,26 +1,104 @@
-use pinocchio::{AccountView, Address, ProgramResult, entrypoint, error::ProgramError};
+use pinocchio::{AccountView, Address, ProgramResult, error::ProgramError};
 use solana_address::declare_id;
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+
+pub mod anchor;
+
+#[cfg(feature = "cpi")]
+pub mod cpi;
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+#[cfg(all(feature = "fuzz", not(target_os = "solana")))]
+pub mod cpi_trace;
+
+pub mod errors;
+pub use errors::*;
+
+pub mod events;
+pub use events::*;
+
+pub mod idl;
 
 pub mod instructions;
 pub use instructions::*;
 
+pub mod pricing;
+pub use pricing::*;
+
+#[cfg(kani)]
+mod proofs;
+
 pub mod state;
 pub use state::*;
 
 // 22222222222222222222222222222222222222222222
 declare_id!("22222222222222222222222222222222222222222222");
 
-fn process_instruction(
+pub fn process_instruction(
     _program_id: &Address,
     accounts: &[AccountView],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    match instruction_data.split_first() {
-        Some((Make::DISCRIMINATOR, data)) => Make::try_from((data, accounts))?.process(),
-        Some((Take::DISCRIMINATOR, _)) => Take::try_from(accounts)?.process(),
-        Some((Refund::DISCRIMINATOR, _)) => Refund::try_from(accounts)?.process(),
+    let (discriminator, data) =
+        split_discriminator(instruction_data).ok_or(ProgramError::InvalidInstructionData)?;
+    check_paused(discriminator, accounts)?;
+
+    match (discriminator, data) {
+        (Make::DISCRIMINATOR, data) => Make::try_from((data, accounts))?.process(),
+        (Take::DISCRIMINATOR, data) => Take::try_from((data, accounts))?.process(),
+        (Refund::DISCRIMINATOR, data) => Refund::try_from((data, accounts))?.process(),
+        (Claim::DISCRIMINATOR, _) => Claim::try_from(accounts)?.process(),
+        (QuoteOffer::DISCRIMINATOR, data) => QuoteOffer::try_from((data, accounts))?.process(),
+        (InitConfig::DISCRIMINATOR, _) => InitConfig::try_from(accounts)?.process(),
+        (SetPause::DISCRIMINATOR, data) => SetPause::try_from((data, accounts))?.process(),
+        (TransferAdmin::DISCRIMINATOR, data) => {
+            TransferAdmin::try_from((data, accounts))?.process()
+        }
+        (AcceptAdmin::DISCRIMINATOR, _) => AcceptAdmin::try_from(accounts)?.process(),
+        #[cfg(feature = "cpi-events")]
+        (EmitEvent::DISCRIMINATOR, _) => EmitEvent::try_from(accounts)?.process(),
+        #[cfg(feature = "mint-policy")]
+        (SetMintPolicy::DISCRIMINATOR, data) => {
+            SetMintPolicy::try_from((data, accounts))?.process()
+        }
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
+
+/// 拆出判别符与参数；`anchor` feature 下同时接受 Anchor 的 8 字节判别符
+#[inline(always)]
+fn split_discriminator(instruction_data: &[u8]) -> Option<(&u8, &[u8])> {
+    #[cfg(feature = "anchor")]
+    if let Some(split) = anchor::split_instruction(instruction_data) {
+        return Some(split);
+    }
+    instruction_data.split_first()
+}
+
+/// 紧急暂停：Make / Take 在对应标志被置位时直接拒绝。
+/// Refund / Claim 与管理指令从不检查，保证创建者随时可以取回资产。
+/// 尚未 InitConfig 的部署视为未暂停，升级程序后无需先初始化 Config。
+#[inline(always)]
+fn check_paused(discriminator: &u8, accounts: &[AccountView]) -> ProgramResult {
+    let (flag, config_index) = match discriminator {
+        Make::DISCRIMINATOR => (Config::PAUSE_MAKE, Make::CONFIG_INDEX),
+        Take::DISCRIMINATOR => (Config::PAUSE_TAKE, Take::CONFIG_INDEX),
+        _ => return Ok(()),
+    };
+
+    let config = accounts
+        .get(config_index)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if !ConfigAccount::is_initialized(config)? {
+        return Ok(());
+    }
+
+    let data = config.try_borrow()?;
+    if Config::load(&data)?.is_paused(flag) {
+        return Err(EscrowError::InstructionPaused.into());
+    }
+    Ok(())
+}
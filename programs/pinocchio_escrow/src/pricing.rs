@@ -0,0 +1,10 @@
+/*
+成交定价：Take 与 Quote 共用 `escrow_core::Quote`，旧版程序的 Take 也用它，二者不会出现偏差。
+
+当前报价只能整单成交：金库中的全部 Token A 换取 `receive` 数量的 Token B，
+若传入推荐人账户，其中按 `referral_bps` 计算的推荐费付给推荐人，收款人得到其余部分。
+
+计算部分只依赖数值，不读取账户，`proofs.rs` 中的 Kani 证明直接针对这些函数。
+ */
+
+pub use escrow_core::{Quote, SettledAmounts, referral_fee};
@@ -0,0 +1,229 @@
+/*
+Kani 证明：对状态布局、定价与关闭账户的纯计算部分做穷举验证 (`cargo kani -p pinocchio_escrow`)。
+
+只在 `cfg(kani)` 下编译；覆盖的性质：
+
+1. `Escrow` / `Config` / `MintPolicy` 的 `load` 当且仅当长度正确时成功，且字段与字节布局一致；
+2. Make 参数解析只接受非零数量、不超过 100% 的推荐费率与已知的结算方式；
+3. 整单成交 (报价不支持部分成交) 时 taker 拿走整个金库、恰好支付 `receive`，其中不超过
+   `bps * receive` 的推荐费付给推荐人，其余归收款人，各数量之和不溢出；
+4. `ProgramAccount::close` 只转移 lamports (不会凭空产生或因溢出丢失)，并清空被关闭账户的
+   数据长度与所有者。
+
+字段偏移通过 `offset_of!` 取自结构体本身，`anchor` feature 下 (开头多出判别符) 同样成立。
+ */
+
+use core::mem::{offset_of, size_of};
+
+use pinocchio::{
+    AccountView, Address,
+    account::{NOT_BORROWED, RuntimeAccount},
+};
+
+use crate::{Config, Escrow, MAX_BPS, MakeInstructionData, MintPolicy, ProgramAccount, Quote};
+
+/// 读取 `bytes[offset..offset + N]`
+fn field<const N: usize>(bytes: &[u8], offset: usize) -> [u8; N] {
+    bytes[offset..offset + N].try_into().unwrap()
+}
+
+#[kani::proof]
+fn escrow_load_checks_length() {
+    let mut buffer: [u8; Escrow::LEN + 1] = kani::any();
+    let len: usize = kani::any();
+    kani::assume(len <= buffer.len());
+
+    let bytes = &buffer[..len];
+    match Escrow::load(bytes) {
+        Ok(escrow) => {
+            assert_eq!(len, Escrow::LEN);
+            assert_eq!(
+                escrow.seed(),
+                u64::from_le_bytes(field(bytes, offset_of!(Escrow, seed)))
+            );
+            assert_eq!(
+                escrow.receive(),
+                u64::from_le_bytes(field(bytes, offset_of!(Escrow, receive)))
+            );
+            assert_eq!(escrow.bump[0], bytes[offset_of!(Escrow, bump)]);
+            assert_eq!(
+                escrow.referral_bps(),
+                u16::from_le_bytes(field(bytes, offset_of!(Escrow, referral_bps)))
+            );
+            assert_eq!(escrow.settlement, bytes[offset_of!(Escrow, settlement)]);
+            assert_eq!(escrow.status, bytes[offset_of!(Escrow, status)]);
+        }
+        Err(_) => assert_ne!(len, Escrow::LEN),
+    }
+
+    assert_eq!(
+        Escrow::load_mut(&mut buffer[..len]).is_ok(),
+        len == Escrow::LEN
+    );
+}
+
+#[kani::proof]
+fn config_load_checks_length() {
+    let mut bytes: [u8; Config::LEN + 1] = kani::any();
+    let len: usize = kani::any();
+    kani::assume(len <= bytes.len());
+
+    match Config::load(&bytes[..len]) {
+        Ok(config) => {
+            assert_eq!(len, Config::LEN);
+            assert_eq!(config.paused, bytes[offset_of!(Config, paused)]);
+            assert_eq!(config.bump[0], bytes[offset_of!(Config, bump)]);
+        }
+        Err(_) => assert_ne!(len, Config::LEN),
+    }
+    assert_eq!(
+        Config::load_mut(&mut bytes[..len]).is_ok(),
+        len == Config::LEN
+    );
+}
+
+#[kani::proof]
+fn mint_policy_load_checks_length() {
+    let mut bytes: [u8; MintPolicy::LEN + 1] = kani::any();
+    let len: usize = kani::any();
+    kani::assume(len <= bytes.len());
+
+    match MintPolicy::load(&bytes[..len]) {
+        Ok(policy) => {
+            assert_eq!(len, MintPolicy::LEN);
+            assert_eq!(policy.status, bytes[offset_of!(MintPolicy, status)]);
+            assert_eq!(policy.bump[0], bytes[offset_of!(MintPolicy, bump)]);
+        }
+        Err(_) => assert_ne!(len, MintPolicy::LEN),
+    }
+    assert_eq!(
+        MintPolicy::load_mut(&mut bytes[..len]).is_ok(),
+        len == MintPolicy::LEN
+    );
+}
+
+#[kani::proof]
+fn make_args_are_validated() {
+    let bytes: [u8; 91] = kani::any();
+    let len: usize = kani::any();
+    kani::assume(len <= bytes.len());
+
+    if let Ok(args) = MakeInstructionData::try_from(&bytes[..len]) {
+        assert!(matches!(len, 24 | 26 | 90 | 91));
+        assert_ne!(args.amount, 0);
+        assert!(args.referral_bps <= MAX_BPS);
+        assert!(matches!(
+            args.settlement,
+            Escrow::SETTLE_PUSH | Escrow::SETTLE_CLAIM
+        ));
+    }
+}
+
+/// 整单成交的定价；报价没有部分成交，`amount_out` 总是整个金库
+#[kani::proof]
+fn full_fill_charges_exactly_receive() {
+    let receive: u64 = kani::any();
+    let referral_bps: u16 = kani::any();
+    let vault_amount: u64 = kani::any();
+    let with_referrer: bool = kani::any();
+    // Make 拒绝超过 MAX_BPS 的费率 (见 `make_args_are_validated`)
+    kani::assume(referral_bps <= MAX_BPS);
+
+    let quote = Quote::price(receive, referral_bps, false, vault_amount, with_referrer);
+
+    // taker 拿走整个金库，恰好支付报价的数量
+    assert!(quote.fillable);
+    assert_eq!(quote.amount_out, vault_amount);
+
+    // taker 恰好支付 receive，推荐费从中分出且不超过报价比例，收款人得到其余部分；
+    // 两部分用 checked 加法还原，不存在被饱和运算掩盖的溢出
+    assert_eq!(quote.amount_in, receive);
+    assert!(quote.fee <= quote.amount_in);
+    assert_eq!(
+        quote.maker_amount().checked_add(quote.fee),
+        Some(quote.amount_in)
+    );
+    assert!(quote.fee as u128 * MAX_BPS as u128 <= receive as u128 * referral_bps as u128);
+    if !with_referrer {
+        assert_eq!(quote.maker_amount(), receive);
+    }
+}
+
+#[kani::proof]
+fn filled_offer_moves_nothing() {
+    let quote = Quote::price(kani::any(), kani::any(), true, kani::any(), kani::any());
+
+    assert!(!quote.fillable);
+    assert_eq!(quote.amount_out, 0);
+    assert_eq!(quote.amount_in, 0);
+    assert_eq!(quote.maker_amount(), 0);
+}
+
+/// 账户数据区的字节数 (`close` 不读写数据本身)
+const DATA_LEN: usize = 64;
+
+/// 按运行时的内存布局 (`RuntimeAccount` 头部后紧跟数据) 分配的账户，8 字节对齐
+struct Account {
+    words: [u64; (size_of::<RuntimeAccount>() + DATA_LEN) / 8],
+}
+
+impl Account {
+    fn any(owner: Address) -> Self {
+        let mut account = Self {
+            words: [0; (size_of::<RuntimeAccount>() + DATA_LEN) / 8],
+        };
+        let data_len: u64 = kani::any();
+        kani::assume(data_len <= DATA_LEN as u64);
+        unsafe {
+            account.raw().write(RuntimeAccount {
+                borrow_state: NOT_BORROWED,
+                is_signer: 0,
+                is_writable: 1,
+                executable: 0,
+                resize_delta: 0,
+                address: Address::new_from_array(kani::any()),
+                owner,
+                lamports: kani::any(),
+                data_len,
+            });
+        }
+        account
+    }
+
+    fn raw(&mut self) -> *mut RuntimeAccount {
+        self.words.as_mut_ptr() as *mut RuntimeAccount
+    }
+
+    fn view(&mut self) -> AccountView {
+        unsafe { AccountView::new_unchecked(self.raw()) }
+    }
+}
+
+#[kani::proof]
+fn close_moves_lamports_and_clears_the_account() {
+    let mut account = Account::any(crate::ID);
+    let mut destination = Account::any(Address::new_from_array(kani::any()));
+    let (account, destination) = (account.view(), destination.view());
+
+    let lamports = account.lamports();
+    let destination_lamports = destination.lamports();
+    let destination_owner = unsafe { *destination.owner() };
+    let total = lamports as u128 + destination_lamports as u128;
+
+    match ProgramAccount::close(&account, &destination) {
+        // 被关闭的账户余额归零、数据清空并归还系统程序，接收者得到全部余额
+        Ok(()) => {
+            assert_eq!(account.lamports(), 0);
+            assert_eq!(destination.lamports() as u128, total);
+            assert_eq!(account.data_len(), 0);
+            assert!(account.owned_by(&Address::new_from_array([0; 32])));
+            assert!(destination.owned_by(&destination_owner));
+        }
+        // 只有余额相加溢出时失败，此时两边都不变
+        Err(_) => {
+            assert!(total > u64::MAX as u128);
+            assert_eq!(account.lamports(), lamports);
+            assert_eq!(destination.lamports(), destination_lamports);
+        }
+    }
+}
@@ -1,88 +1,134 @@
 use core::mem::size_of;
+use escrow_core::Layout;
 use pinocchio::{Address, error::ProgramError};
 
-// --- 定义常量种子 ---
-pub const ESCROW_SEED: &[u8] = b"escrow";
+// 托管种子与费率基数 (10_000 bps = 100%) 与旧版程序共用
+pub use escrow_core::{ESCROW_SEED, MAX_BPS};
+
+// 托管记录的布局由 `escrow_core` 定义，与旧版程序共用；地址字段在这里取 0.10 的 `Address`
+pub type Escrow = escrow_core::Escrow<Address>;
+
+/// 旧版托管布局 (113 字节)：引入推荐费与接收者字段之前创建的托管
+/// (包括旧版程序 `solana_pinocchio_escrow` 以同一程序 ID 创建的报价)，
+/// 按无推荐费、创建者即租金接收者与收款人处理
+pub type LegacyEscrow = escrow_core::LegacyEscrow<Address>;
+
+/// 按账户长度区分的托管记录：当前布局，或旧版布局
+pub type EscrowVersion<'a> = escrow_core::EscrowVersion<'a, Address>;
+
+// --- 全局配置 (管理员 / 紧急暂停) ---
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Config PDA 的规范 bump 与地址，编译期推导，热路径上不必 `find_program_address`
+/// (程序 ID 变化后须同步更新 bump，`escrow-client` 的测试会比对二者)
+pub const CONFIG_BUMP: u8 = 251;
+pub const CONFIG_ADDRESS: Address =
+    Address::derive_address_const(&[CONFIG_SEED], Some(CONFIG_BUMP), &crate::ID);
 
 #[repr(C)]
-pub struct Escrow {
-    pub seed: u64,       // Random seed for PDA derivation
-    pub maker: Address,  // Creator of the escrow
-    pub mint_a: Address, // Token being deposited
-    pub mint_b: Address, // Token being requested
-    pub receive: u64,    // Amount of token B wanted
-    pub bump: [u8; 1],   // PDA bump seed
+pub struct Config {
+    pub admin: Address,         // Current admin
+    pub pending_admin: Address, // Proposed admin, all zeroes when none
+    pub paused: u8,             // Bit set of paused instructions
+    pub bump: [u8; 1],          // PDA bump seed
+}
+
+unsafe impl Layout for Config {
+    const LEN: usize = Config::LEN;
 }
 
-impl Escrow {
-    pub const LEN: usize = size_of::<u64>()
-        + size_of::<Address>()
-        + size_of::<Address>()
-        + size_of::<Address>()
-        + size_of::<u64>()
-        + size_of::<[u8; 1]>();
+impl Config {
+    pub const LEN: usize =
+        size_of::<Address>() + size_of::<Address>() + size_of::<u8>() + size_of::<[u8; 1]>();
+
+    // 可暂停的指令，Refund 故意不在其中，创建者任何时候都能取回资产
+    pub const PAUSE_MAKE: u8 = 1 << 0;
+    pub const PAUSE_TAKE: u8 = 1 << 1;
+    pub const PAUSABLE: u8 = Self::PAUSE_MAKE | Self::PAUSE_TAKE;
 
     #[inline(always)]
     pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+        Ok(escrow_core::load_mut(bytes)?)
     }
 
     #[inline(always)]
     pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+        Ok(escrow_core::load(bytes)?)
     }
 
     #[inline(always)]
-    pub fn set_seed(&mut self, seed: u64) {
-        self.seed = seed;
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
     }
 
     #[inline(always)]
-    pub fn set_maker(&mut self, maker: Address) {
-        self.maker = maker;
+    pub fn has_pending_admin(&self) -> bool {
+        self.pending_admin != Address::default()
     }
 
     #[inline(always)]
-    pub fn set_mint_a(&mut self, mint_a: Address) {
-        self.mint_a = mint_a;
+    pub fn set_admin(&mut self, admin: Address) {
+        self.admin = admin;
     }
 
     #[inline(always)]
-    pub fn set_mint_b(&mut self, mint_b: Address) {
-        self.mint_b = mint_b;
+    pub fn set_pending_admin(&mut self, pending_admin: Address) {
+        self.pending_admin = pending_admin;
     }
 
     #[inline(always)]
-    pub fn set_receive(&mut self, receive: u64) {
-        self.receive = receive;
+    pub fn set_paused(&mut self, paused: u8) {
+        self.paused = paused;
     }
 
     #[inline(always)]
-    pub fn set_bump(&mut self, bump: [u8; 1]) {
+    pub fn set_inner(&mut self, admin: Address, bump: [u8; 1]) {
+        self.admin = admin;
+        self.pending_admin = Address::default();
+        self.paused = 0;
         self.bump = bump;
     }
+}
+
+// --- Mint 准入策略 (每个 mint 一个 PDA，需启用 `mint-policy` feature) ---
+pub const MINT_POLICY_SEED: &[u8] = b"mint_policy";
+
+#[repr(C)]
+pub struct MintPolicy {
+    pub mint: Address, // Mint this policy applies to
+    pub status: u8,    // `MintPolicy::ALLOWED` or `MintPolicy::DENIED`
+    pub bump: [u8; 1], // PDA bump seed
+}
+
+unsafe impl Layout for MintPolicy {
+    const LEN: usize = MintPolicy::LEN;
+}
+
+impl MintPolicy {
+    pub const LEN: usize = size_of::<Address>() + size_of::<u8>() + size_of::<[u8; 1]>();
+
+    pub const ALLOWED: u8 = 1;
+    pub const DENIED: u8 = 2;
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        Ok(escrow_core::load_mut(bytes)?)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        Ok(escrow_core::load(bytes)?)
+    }
+
+    #[inline(always)]
+    pub fn set_status(&mut self, status: u8) {
+        self.status = status;
+    }
 
     #[inline(always)]
-    pub fn set_inner(
-        &mut self,
-        seed: u64,
-        maker: Address,
-        mint_a: Address,
-        mint_b: Address,
-        receive: u64,
-        bump: [u8; 1],
-    ) {
-        self.seed = seed;
-        self.maker = maker;
-        self.mint_a = mint_a;
-        self.mint_b = mint_b;
-        self.receive = receive;
+    pub fn set_inner(&mut self, mint: Address, status: u8, bump: [u8; 1]) {
+        self.mint = mint;
+        self.status = status;
         self.bump = bump;
     }
 }
@@ -1,23 +1,12 @@
+use escrow_core::close_lamports;
 use pinocchio::instruction::{Seed, Signer};
-use pinocchio::program_error::ProgramError;
 use pinocchio::{ProgramResult, account_info::AccountInfo};
 use pinocchio_associated_token_account::instructions::CreateIdempotent;
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::state::TokenAccount;
 
-// --- 1. 签名者检查助手 ---
-pub struct SignerAccount;
-impl SignerAccount {
-    #[inline(always)]
-    pub fn check(account: &AccountInfo) -> Result<(), ProgramError> {
-        if !account.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        Ok(())
-    }
-}
+// 账户检查由各指令账户结构体上的 `#[derive(Accounts)]` 约束完成 (见 `escrow_core::validate`)
 
-// --- 2. 程序账户 (PDA/State) 助手 ---
+// --- 1. 程序账户 (PDA/State) 助手 ---
 pub struct ProgramAccount;
 impl ProgramAccount {
     #[inline(always)]
@@ -41,19 +30,10 @@ impl ProgramAccount {
         .invoke_signed(&[Signer::from(signer_seeds)])
     }
 
-    /// 检查该账户是否由本程序拥有
-    #[inline(always)]
-    pub fn check(account: &AccountInfo) -> Result<(), ProgramError> {
-        if account.owner() != &crate::ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(())
-    }
-
     /// 关闭账户并回收 Lamports (常用于 Refund/Take)
     pub fn close(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
-        // 将 lamports 转移给接收者
-        let lamports = account.lamports();
+        // 将 lamports 转移给接收者 (溢出时报错)
+        let balance = close_lamports(account.lamports(), destination.lamports())?;
         // 2. 手动转移 Lamports
         // 注意：在 Pinocchio 0.9.2 源码中，修改 lamports 需要通过 unsafe 的 unchecked 方法
         // 或者使用 try_borrow_mut_lamports (会增加 CU 开销)
@@ -61,8 +41,8 @@ impl ProgramAccount {
         unsafe {
             // 将原账户余额清零
             *account.borrow_mut_lamports_unchecked() = 0;
-            // 将余额累加到接收者账户
-            *destination.borrow_mut_lamports_unchecked() += lamports;
+            // 接收者得到两者之和
+            *destination.borrow_mut_lamports_unchecked() = balance;
         }
 
         // 清理数据并将所有者重置为系统程序
@@ -70,22 +50,7 @@ impl ProgramAccount {
     }
 }
 
-// --- 3. Mint (代币定义) 助手 ---
-pub struct MintInterface;
-impl MintInterface {
-    #[inline(always)]
-    pub fn check(account: &AccountInfo) -> Result<(), ProgramError> {
-        // SPL Token Mint 固定长度为 82
-        // 且必须由 Token Program 拥有
-        if account.data_len() != 82 {
-            // SPL Token Mint 固定长度
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(())
-    }
-}
-
-// --- 4. 关联代币账户 (ATA) 助手 ---
+// --- 2. 关联代币账户 (ATA) 助手 ---
 pub struct AssociatedTokenAccount;
 impl AssociatedTokenAccount {
     pub fn init(
@@ -125,17 +90,4 @@ impl AssociatedTokenAccount {
         }
         .invoke()
     }
-
-    pub fn check(
-        ata: &AccountInfo,
-        owner: &AccountInfo,
-        mint: &AccountInfo,
-        _token_program: &AccountInfo,
-    ) -> Result<(), ProgramError> {
-        let token_account = TokenAccount::from_account_info(ata)?;
-        if token_account.owner() != owner.key() || token_account.mint() != mint.key() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(())
-    }
 }
@@ -6,64 +6,35 @@
     3. 使用 CPI 调用 SPL-Token 程序，将创建者的 Token A 转移到该金库中。
 */
 
+use escrow_derive::Accounts;
 use pinocchio::{
     ProgramResult, account_info::AccountInfo, instruction::Seed, program_error::ProgramError,
     pubkey::find_program_address,
 };
 use pinocchio_token::instructions::Transfer;
 
-use crate::{
-    AssociatedTokenAccount, ESCROW_SEED, Escrow, MintInterface, ProgramAccount, SignerAccount,
-};
+use crate::{AssociatedTokenAccount, ESCROW_SEED, Escrow, ProgramAccount};
 
+#[derive(Accounts)]
 pub struct MakeAccounts<'a> {
+    #[account(signer, writable)]
     pub maker: &'a AccountInfo,
+    #[account(writable)]
     pub escrow: &'a AccountInfo,
+    #[account(mint)]
     pub mint_a: &'a AccountInfo,
+    #[account(mint)]
     pub mint_b: &'a AccountInfo,
+    #[account(writable, token::mint = mint_a, token::authority = maker)]
     pub maker_ata_a: &'a AccountInfo,
+    #[account(writable)]
     pub vault: &'a AccountInfo,
+    #[account(address = pinocchio_system::ID)]
     pub system_program: &'a AccountInfo,
+    #[account(address = pinocchio_token::ID)]
     pub token_program: &'a AccountInfo,
-}
-
-impl<'a> TryFrom<&'a [AccountInfo]> for MakeAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            maker_ata_a,
-            vault,
-            system_program,
-            token_program,
-            _,
-        ] = accounts
-        else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-        // Basic Accounts Checks
-        SignerAccount::check(maker)?;
-        MintInterface::check(mint_a)?;
-        MintInterface::check(mint_b)?;
-        AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
-
-        // Return the accounts
-        Ok(Self {
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            maker_ata_a,
-            vault,
-            system_program,
-            token_program,
-        })
-    }
+    #[account(address = pinocchio_associated_token_account::ID)]
+    pub associated_token_program: &'a AccountInfo,
 }
 
 pub struct MakeInstructionData {
@@ -107,7 +78,7 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Make<'a> {
     type Error = ProgramError;
 
     fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
-        let accounts = MakeAccounts::try_from(accounts)?;
+        let (accounts, _) = MakeAccounts::try_accounts(accounts)?;
         let instruction_data = MakeInstructionData::try_from(data)?;
 
         // Initialize the Accounts needed
@@ -8,6 +8,7 @@ refund 指令允许创建者取消一个未完成的报价：
 
 use std::slice;
 
+use escrow_derive::Accounts;
 use pinocchio::{
     ProgramResult,
     account_info::AccountInfo,
@@ -21,81 +22,82 @@ use pinocchio_token::{
 
 use crate::{AssociatedTokenAccount, ESCROW_SEED, Escrow, ProgramAccount};
 
-pub struct Refund<'a> {
+/// 只有创建者本人能取消报价：托管 PDA 由其地址推导，金库必须是托管 PDA 的 mint_a ATA
+#[derive(Accounts)]
+pub struct RefundAccounts<'a> {
+    #[account(signer, writable)]
     pub maker: &'a AccountInfo,
+    #[account(
+        writable,
+        owner = crate::ID,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), &Escrow::seed_bytes_of(escrow)?],
+        bump = Escrow::bump_of(escrow)?,
+    )]
     pub escrow: &'a AccountInfo,
+    #[account(mint, address = Escrow::mint_a_of(escrow)?)]
     pub mint_a: &'a AccountInfo,
+    #[account(writable, associated_token::mint = mint_a, associated_token::authority = escrow)]
     pub vault: &'a AccountInfo,
+    #[account(writable)]
     pub maker_ata_a: &'a AccountInfo,
+    #[account(address = pinocchio_associated_token_account::ID)]
     pub associated_token_program: &'a AccountInfo,
+    #[account(address = pinocchio_token::ID)]
     pub token_program: &'a AccountInfo,
+    #[account(address = pinocchio_system::ID)]
     pub system_program: &'a AccountInfo,
 }
 
+pub struct Refund<'a> {
+    pub accounts: RefundAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for Refund<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let (accounts, _) = RefundAccounts::try_accounts(accounts)?;
+        Ok(Self { accounts })
+    }
+}
+
 impl<'a> Refund<'a> {
     pub const DISCRIMINATOR: &'a u8 = &2;
-    pub fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
-        // 使用简单的切片模式匹配来获取账户，性能最优
-        let [
-            maker,       // 1. Signer
-            escrow,      // 2. Escrow PDA
-            mint_a,      // 3. Mint A (Anchor 里的第三个账户)
-            vault,       // 4. Vault (Token Account)
-            maker_ata_a, // 5. Maker ATA
-            associated_token_program,
-            token_program,  // 6. Token Program
-            system_program, // 7. System Program
-        ] = accounts
-        else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-        Ok(Self {
-            maker,
-            escrow,
-            mint_a,
-            vault,
-            maker_ata_a,
-            associated_token_program,
-            token_program,
-            system_program,
-        })
-    }
 
     pub fn process(&self) -> ProgramResult {
         AssociatedTokenAccount::init_if_needed(
-            self.maker_ata_a,
-            self.mint_a,
-            self.maker,
-            self.maker,
-            self.system_program,
-            self.token_program,
+            self.accounts.maker_ata_a,
+            self.accounts.mint_a,
+            self.accounts.maker,
+            self.accounts.maker,
+            self.accounts.system_program,
+            self.accounts.token_program,
         )?;
 
         // 1. 获取 Escrow 数据视图 (零拷贝)
-        let data = self.escrow.try_borrow_data()?;
+        let data = self.accounts.escrow.try_borrow_data()?;
         let escrow_state = Escrow::load(&data)?;
 
         // 2. 构造 PDA 签名
-        let seed_bytes = escrow_state.seed.to_le_bytes();
+        let seed_bytes = escrow_state.seed;
         let seeds = [
             Seed::from(ESCROW_SEED),
-            Seed::from(self.maker.key().as_ref()),
+            Seed::from(self.accounts.maker.key().as_ref()),
             Seed::from(&seed_bytes),
             Seed::from(&escrow_state.bump),
         ];
         let signer = Signer::from(&seeds);
 
         // 检查 amount 之前确保 vault 数据有效
-        let amount = TokenAccount::from_account_info(self.vault)?.amount();
+        let amount = TokenAccount::from_account_info(self.accounts.vault)?.amount();
 
         if amount > 0 {
             // 执行转账: Vault (from) -> Maker ATA (to)
             // 必须确认识别到的 vault 账户的所有者是 escrow PDA
             Transfer {
-                from: self.vault,
-                to: self.maker_ata_a,
-                authority: self.escrow, // 这里必须是 PDA
+                from: self.accounts.vault,
+                to: self.accounts.maker_ata_a,
+                authority: self.accounts.escrow, // 这里必须是 PDA
                 amount,
             }
             .invoke_signed(slice::from_ref(&signer))?;
@@ -103,14 +105,14 @@ impl<'a> Refund<'a> {
 
         // 关闭 Vault 账户
         CloseAccount {
-            account: self.vault,
-            destination: self.maker,
-            authority: self.escrow,
+            account: self.accounts.vault,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
         }
         .invoke_signed(&[signer])?;
 
         drop(data); // 必须在 close escrow 前释放
-        ProgramAccount::close(self.escrow, self.maker)?;
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
 
         Ok(())
     }
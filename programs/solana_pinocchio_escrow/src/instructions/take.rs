@@ -1,20 +1,19 @@
 use std::slice;
 
+use escrow_core::Quote;
+use escrow_derive::Accounts;
 use pinocchio::{
     ProgramResult,
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
-    pubkey::create_program_address,
 };
 use pinocchio_token::{
     instructions::{CloseAccount, Transfer},
     state::TokenAccount,
 };
 
-use crate::{
-    AssociatedTokenAccount, ESCROW_SEED, Escrow, MintInterface, ProgramAccount, SignerAccount,
-};
+use crate::{AssociatedTokenAccount, ESCROW_SEED, Escrow, ProgramAccount};
 
 /*
     1. 关闭托管记录，将其租金 lamports 返还给创建者。
@@ -22,66 +21,40 @@ use crate::{
     2. 将 Token A 从保管库转移到接受者，然后关闭保管库。
 
     3. 将约定数量的 Token B 从接受者转移到创建者。
+
+    两个 mint 必须与托管记录一致，金库必须是托管 PDA 的 mint_a ATA。
 */
+#[derive(Accounts)]
 pub struct TakeAccounts<'a> {
+    #[account(signer, writable)]
     pub taker: &'a AccountInfo,
+    #[account(writable)]
     pub maker: &'a AccountInfo,
+    #[account(
+        writable,
+        owner = crate::ID,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), &Escrow::seed_bytes_of(escrow)?],
+        bump = Escrow::bump_of(escrow)?,
+    )]
     pub escrow: &'a AccountInfo,
+    #[account(mint, address = Escrow::mint_a_of(escrow)?)]
     pub mint_a: &'a AccountInfo,
+    #[account(mint, address = Escrow::mint_b_of(escrow)?)]
     pub mint_b: &'a AccountInfo,
+    #[account(writable, associated_token::mint = mint_a, associated_token::authority = escrow)]
     pub vault: &'a AccountInfo,
+    #[account(writable)]
     pub taker_ata_a: &'a AccountInfo,
+    #[account(writable, token::mint = mint_b, token::authority = taker)]
     pub taker_ata_b: &'a AccountInfo,
+    #[account(writable)]
     pub maker_ata_b: &'a AccountInfo,
+    #[account(address = pinocchio_system::ID)]
     pub system_program: &'a AccountInfo,
+    #[account(address = pinocchio_token::ID)]
     pub token_program: &'a AccountInfo,
-}
-
-impl<'a> TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [
-            taker,
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            vault,
-            taker_ata_a,
-            taker_ata_b,
-            maker_ata_b,
-            system_program,
-            token_program,
-            _,
-        ] = accounts
-        else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-        // Basic Accounts Checks
-        SignerAccount::check(taker)?;
-        ProgramAccount::check(escrow)?;
-        MintInterface::check(mint_a)?;
-        MintInterface::check(mint_b)?;
-        AssociatedTokenAccount::check(taker_ata_b, taker, mint_b, token_program)?;
-        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
-
-        // Return the accounts
-        Ok(Self {
-            taker,
-            maker,
-            escrow,
-            mint_a,
-            mint_b,
-            taker_ata_a,
-            taker_ata_b,
-            maker_ata_b,
-            vault,
-            system_program,
-            token_program,
-        })
-    }
+    #[account(address = pinocchio_associated_token_account::ID)]
+    pub associated_token_program: &'a AccountInfo,
 }
 
 pub struct Take<'a> {
@@ -92,7 +65,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for Take<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let accounts = TakeAccounts::try_from(accounts)?;
+        let (accounts, _) = TakeAccounts::try_accounts(accounts)?;
 
         // Initialize necessary accounts
         AssociatedTokenAccount::init_if_needed(
@@ -124,21 +97,7 @@ impl<'a> Take<'a> {
         let data = self.accounts.escrow.try_borrow_data()?;
         let escrow = Escrow::load(&data)?;
 
-        // Check if the escrow is valid
-        let escrow_key = create_program_address(
-            &[
-                ESCROW_SEED,
-                self.accounts.maker.key(),
-                &escrow.seed.to_le_bytes(),
-                &escrow.bump,
-            ],
-            &crate::ID,
-        )?;
-        if &escrow_key != self.accounts.escrow.key() {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-
-        let seed_binding = escrow.seed.to_le_bytes();
+        let seed_binding = escrow.seed;
         let bump_binding = escrow.bump;
         let escrow_seeds = [
             Seed::from(ESCROW_SEED),
@@ -150,12 +109,15 @@ impl<'a> Take<'a> {
 
         let amount = TokenAccount::from_account_info(self.accounts.vault)?.amount();
 
+        // 与新版程序同一份定价；旧版布局没有推荐费
+        let quote = Quote::price(escrow.receive(), 0, false, amount, false);
+
         // Transfer from the Vault to the Taker
         Transfer {
             from: self.accounts.vault,
             to: self.accounts.taker_ata_a,
             authority: self.accounts.escrow,
-            amount,
+            amount: quote.amount_out,
         }
         .invoke_signed(slice::from_ref(&signer))?;
 
@@ -172,13 +134,13 @@ impl<'a> Take<'a> {
             from: self.accounts.taker_ata_b,
             to: self.accounts.maker_ata_b,
             authority: self.accounts.taker,
-            amount: escrow.receive,
+            amount: quote.amount_in,
         }
         .invoke()?;
 
         // Close the Escrow
         drop(data);
-        ProgramAccount::close(self.accounts.escrow, self.accounts.taker)?;
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
 
         Ok(())
     }
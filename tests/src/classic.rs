@@ -0,0 +1,131 @@
+/*
+旧版 `solana_pinocchio_escrow` 的指令构造。
+
+旧版没有客户端 crate，也没有 `ACCOUNTS` 表；账户顺序照抄其各指令 `try_from` 的切片模式。
+两个程序使用同一个程序 ID 与相同的托管 / 金库种子，地址推导直接复用 `escrow_client`。
+ */
+
+use escrow_client::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, AccountMeta, Address, Instruction, PROGRAM_ID, SYSTEM_PROGRAM_ID,
+    TOKEN_PROGRAM_ID, find_escrow_address, get_associated_token_address, get_vault_address,
+};
+
+/// 旧版托管账户长度：seed | maker | mint_a | mint_b | receive | bump
+pub const ESCROW_LEN: usize = 8 + 32 * 3 + 8 + 1;
+
+/// 旧版 Make 固定以该数量的 lamports 创建托管账户 (不按 Rent sysvar 计算)
+pub const ESCROW_LAMPORTS: u64 = 2_000_000;
+
+/// 解码后的旧版托管账户
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassicEscrow {
+    pub seed: u64,
+    pub maker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub receive: u64,
+    pub bump: u8,
+}
+
+impl ClassicEscrow {
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != ESCROW_LEN {
+            return None;
+        }
+        let address =
+            |offset: usize| Address::new_from_array(data[offset..offset + 32].try_into().unwrap());
+        Some(Self {
+            seed: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            maker: address(8),
+            mint_a: address(40),
+            mint_b: address(72),
+            receive: u64::from_le_bytes(data[104..112].try_into().unwrap()),
+            bump: data[112],
+        })
+    }
+}
+
+pub fn make(
+    maker: &Address,
+    mint_a: &Address,
+    mint_b: &Address,
+    seed: u64,
+    receive: u64,
+    amount: u64,
+) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, seed);
+
+    let mut data = Vec::with_capacity(1 + 24);
+    data.push(0);
+    data.extend_from_slice(&seed.to_le_bytes());
+    data.extend_from_slice(&receive.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction::new_with_bytes(
+        PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(ata(maker, mint_a), false),
+            AccountMeta::new(get_vault_address(&escrow, mint_a), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+    )
+}
+
+pub fn take(
+    taker: &Address,
+    maker: &Address,
+    mint_a: &Address,
+    mint_b: &Address,
+    seed: u64,
+) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, seed);
+
+    Instruction::new_with_bytes(
+        PROGRAM_ID,
+        &[1],
+        vec![
+            AccountMeta::new(*taker, true),
+            AccountMeta::new(*maker, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(get_vault_address(&escrow, mint_a), false),
+            AccountMeta::new(ata(taker, mint_a), false),
+            AccountMeta::new(ata(taker, mint_b), false),
+            AccountMeta::new(ata(maker, mint_b), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+    )
+}
+
+pub fn refund(maker: &Address, mint_a: &Address, seed: u64) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, seed);
+
+    Instruction::new_with_bytes(
+        PROGRAM_ID,
+        &[2],
+        vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new(get_vault_address(&escrow, mint_a), false),
+            AccountMeta::new(ata(maker, mint_a), false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    )
+}
+
+fn ata(owner: &Address, mint: &Address) -> Address {
+    get_associated_token_address(owner, mint, &TOKEN_PROGRAM_ID)
+}
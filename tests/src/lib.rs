@@ -0,0 +1,197 @@
+/*
+进程内 SVM 测试工具：用 Mollusk 加载 `cargo build-sbf` 生成的程序，无需验证节点。
+
+依赖这些程序的用例标记为 `#[ignore]`，由 `just test-sbf` (`--include-ignored`) 运行。
+`target/deploy/<program>.so` 不存在时 `Harness::new` 返回 `None`，用例应直接返回 (跳过)；
+设置 `ESCROW_REQUIRE_SBF=1` 时改为 panic，CI 用它保证用例确实执行。
+ */
+
+pub mod classic;
+pub mod model;
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use escrow_client::{
+    Address, EscrowAccount, Instruction, PROGRAM_ID, TOKEN_PROGRAM_ID, find_config_address,
+    get_associated_token_address, program::Config,
+};
+use mollusk_svm::{Mollusk, MolluskContext, program::loader_keys, result::InstructionResult};
+use mollusk_svm_programs_token::{associated_token, token};
+use solana_account::Account;
+
+pub use mollusk_svm;
+pub use solana_account;
+
+/// 足够支付任何账户租金与手续费的 lamports
+pub const LAMPORTS: u64 = 10_000_000_000;
+
+// --- SPL Token 账户布局 ---
+pub const MINT_LEN: usize = 82;
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// 读取 `target/deploy/<name>.so`；不存在时按 `ESCROW_REQUIRE_SBF` 决定跳过或失败
+pub fn load_program(name: &str) -> Option<Vec<u8>> {
+    let path = deploy_dir().join(format!("{name}.so"));
+    match fs::read(&path) {
+        Ok(elf) => Some(elf),
+        Err(_) if std::env::var("ESCROW_REQUIRE_SBF").is_ok() => {
+            panic!("{} is missing; run `cargo build-sbf` first", path.display())
+        }
+        Err(_) => {
+            eprintln!(
+                "skipping: {} not built (run `cargo build-sbf`)",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+fn deploy_dir() -> PathBuf {
+    match std::env::var("SBF_OUT_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../target/deploy")),
+    }
+}
+
+pub struct Harness {
+    pub context: MolluskContext<HashMap<Address, Account>>,
+}
+
+impl Harness {
+    /// 加载 `pinocchio_escrow`、SPL Token 与 ATA 程序，并预置未暂停的 Config
+    pub fn new() -> Option<Self> {
+        let harness = Self::with_program(&PROGRAM_ID, "pinocchio_escrow")?;
+        harness.set_config(&Address::new_unique(), 0);
+        Some(harness)
+    }
+
+    /// 加载旧版 `solana_pinocchio_escrow` (与新版共用程序 ID，无需 Config)
+    pub fn classic() -> Option<Self> {
+        Self::with_program(&PROGRAM_ID, "solana_pinocchio_escrow")
+    }
+
+    /// 加载任意一个托管程序 (例如旧版 crate) 及 SPL Token / ATA 程序
+    pub fn with_program(program_id: &Address, name: &str) -> Option<Self> {
+        let elf = load_program(name)?;
+
+        let mut mollusk = Mollusk::default();
+        mollusk.add_program_with_elf_and_loader(program_id, &elf, &loader_keys::LOADER_V3);
+        token::add_program(&mut mollusk);
+        associated_token::add_program(&mut mollusk);
+
+        Some(Self {
+            context: mollusk.with_context(HashMap::new()),
+        })
+    }
+
+    pub fn process(&self, instruction: &Instruction) -> InstructionResult {
+        self.context.process_instruction(instruction)
+    }
+
+    pub fn rent_exempt(&self, len: usize) -> u64 {
+        self.context.mollusk.sysvars.rent.minimum_balance(len)
+    }
+
+    pub fn set_account(&self, address: Address, account: Account) {
+        self.context
+            .account_store
+            .borrow_mut()
+            .insert(address, account);
+    }
+
+    /// 账户当前状态；已关闭 (lamports 为 0) 的账户视为不存在
+    pub fn account(&self, address: &Address) -> Option<Account> {
+        self.context
+            .account_store
+            .borrow()
+            .get(address)
+            .filter(|account| account.lamports > 0)
+            .cloned()
+    }
+
+    pub fn lamports(&self, address: &Address) -> u64 {
+        self.account(address).map_or(0, |account| account.lamports)
+    }
+
+    /// 代币账户余额 (账户不存在时为 0)
+    pub fn token_amount(&self, address: &Address) -> u64 {
+        self.account(address).map_or(0, |account| {
+            u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+        })
+    }
+
+    /// 解码托管账户 (已关闭或不属于本程序时返回 `None`)
+    pub fn escrow(&self, address: &Address) -> Option<EscrowAccount> {
+        let account = self.account(address)?;
+        EscrowAccount::from_account(&account.owner, &account.data).ok()
+    }
+
+    /// 创建一个由系统程序拥有、余额为 `LAMPORTS` 的钱包
+    pub fn wallet(&self) -> Address {
+        let address = Address::new_unique();
+        self.add_wallet(&address);
+        address
+    }
+
+    /// 在指定地址创建钱包 (多个 `Harness` 之间共用同一组地址时使用)
+    pub fn add_wallet(&self, address: &Address) {
+        self.set_account(*address, Account::new(LAMPORTS, 0, &Address::default()));
+    }
+
+    /// 创建一个已初始化的 mint (6 位小数，无 mint authority)
+    pub fn mint(&self) -> Address {
+        let address = Address::new_unique();
+        self.add_mint(&address);
+        address
+    }
+
+    /// 在指定地址创建 mint
+    pub fn add_mint(&self, address: &Address) {
+        let mut data = vec![0u8; MINT_LEN];
+        data[44] = 6; // decimals
+        data[45] = 1; // is_initialized
+        self.set_account(*address, self.token_program_account(data));
+    }
+
+    /// 创建 `owner` 的 mint ATA 并写入余额，返回其地址
+    pub fn token_account(&self, owner: &Address, mint: &Address, amount: u64) -> Address {
+        let address = get_associated_token_address(owner, mint, &TOKEN_PROGRAM_ID);
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = 1; // AccountState::Initialized
+        self.set_account(address, self.token_program_account(data));
+        address
+    }
+
+    /// 写入 Config PDA (管理员与暂停标志)
+    pub fn set_config(&self, admin: &Address, paused: u8) {
+        let (address, bump) = find_config_address();
+        let mut data = vec![0u8; Config::LEN];
+        data[0..32].copy_from_slice(admin.as_ref());
+        data[64] = paused;
+        data[65] = bump;
+        self.set_account(
+            address,
+            Account {
+                lamports: self.rent_exempt(Config::LEN),
+                data,
+                owner: PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    fn token_program_account(&self, data: Vec<u8>) -> Account {
+        Account {
+            lamports: self.rent_exempt(data.len()),
+            data,
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+/*
+托管语义的参考模型：只用 `BTreeMap` 记录代币账户与报价，不涉及 SVM、CPI 与租金。
+
+与 `pinocchio_escrow` 的 Make / Take / Refund 一一对应 (Push 结算，租金接收者与收款人均为 maker)，
+属性测试对同一串操作分别驱动模型与真实程序，比较每一步的成败与余额。
+
+每条操作先在副本上执行，成功才提交，与交易失败时整体回滚一致。
+ */
+
+use std::collections::BTreeMap;
+
+use escrow_client::{
+    Address, TOKEN_PROGRAM_ID, find_escrow_address, get_associated_token_address,
+    get_vault_address,
+    program::{Escrow, MAX_BPS, MakeInstructionData},
+};
+
+/// 模型拒绝一条操作的原因；与程序比较时只看成败，不比较具体错误
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelError {
+    /// 指令参数非法 (数量为 0、推荐费率超过 100%)
+    InvalidArgument,
+    /// 托管账户已存在
+    AlreadyInUse,
+    /// 托管账户或代币账户不存在
+    MissingAccount,
+    /// 代币余额不足
+    InsufficientFunds,
+    /// 推荐人是报价的创建者或接受者
+    InvalidReferrer,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenAccount {
+    pub mint: Address,
+    pub owner: Address,
+    pub amount: u64,
+}
+
+/// 一笔未成交的报价
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Offer {
+    pub seed: u64,
+    pub maker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub receive: u64,
+    pub referral_bps: u16,
+}
+
+/// 推荐费：`receive` 的 `referral_bps`，向下取整
+fn fee(receive: u64, referral_bps: u16) -> u64 {
+    (receive as u128 * referral_bps as u128 / MAX_BPS as u128) as u64
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    pub tokens: BTreeMap<Address, TokenAccount>,
+    pub offers: BTreeMap<Address, Offer>,
+}
+
+impl Model {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 与 `Harness::token_account` 对应：创建 `owner` 的 mint ATA 并写入余额
+    pub fn token_account(&mut self, owner: &Address, mint: &Address, amount: u64) -> Address {
+        let address = get_associated_token_address(owner, mint, &TOKEN_PROGRAM_ID);
+        self.tokens.insert(
+            address,
+            TokenAccount {
+                mint: *mint,
+                owner: *owner,
+                amount,
+            },
+        );
+        address
+    }
+
+    /// 代币账户余额 (账户不存在时为 `None`)
+    pub fn balance(&self, address: &Address) -> Option<u64> {
+        self.tokens.get(address).map(|account| account.amount)
+    }
+
+    /// 某个 mint 在所有代币账户中的余额总和；任何操作都不应改变它
+    pub fn supply(&self, mint: &Address) -> u128 {
+        self.tokens
+            .values()
+            .filter(|account| &account.mint == mint)
+            .map(|account| account.amount as u128)
+            .sum()
+    }
+
+    /// 只支持 Push 结算与默认的租金接收者 / 收款人
+    pub fn make(
+        &mut self,
+        maker: &Address,
+        mint_a: &Address,
+        mint_b: &Address,
+        args: &MakeInstructionData,
+    ) -> Result<(), ModelError> {
+        assert!(
+            args.settlement == Escrow::SETTLE_PUSH
+                && args.rent_recipient.is_none()
+                && args.proceeds_recipient.is_none(),
+            "the model only covers push settlement with default recipients"
+        );
+
+        self.atomically(|model| {
+            if args.amount == 0 || args.referral_bps > MAX_BPS {
+                return Err(ModelError::InvalidArgument);
+            }
+            let maker_ata_a = model.ata(maker, mint_a)?;
+
+            let (escrow, _) = find_escrow_address(maker, args.seed);
+            if model.offers.contains_key(&escrow) {
+                return Err(ModelError::AlreadyInUse);
+            }
+            model.offers.insert(
+                escrow,
+                Offer {
+                    seed: args.seed,
+                    maker: *maker,
+                    mint_a: *mint_a,
+                    mint_b: *mint_b,
+                    receive: args.receive,
+                    referral_bps: args.referral_bps,
+                },
+            );
+
+            let vault = model.init_ata(&escrow, mint_a);
+            model.transfer(&maker_ata_a, &vault, args.amount)
+        })
+    }
+
+    /// `referrer` 为推荐人钱包 (不能是 taker、maker 或收款人，模型中收款人总是 maker)，
+    /// 推荐费打入其 mint_b ATA
+    pub fn take(
+        &mut self,
+        taker: &Address,
+        escrow: &Address,
+        referrer: Option<&Address>,
+    ) -> Result<(), ModelError> {
+        self.atomically(|model| {
+            let offer = model
+                .offers
+                .remove(escrow)
+                .ok_or(ModelError::MissingAccount)?;
+            if referrer.is_some_and(|referrer| referrer == taker || referrer == &offer.maker) {
+                return Err(ModelError::InvalidReferrer);
+            }
+            let taker_ata_b = model.ata(taker, &offer.mint_b)?;
+            let referrer_ata_b = referrer
+                .map(|referrer| model.ata(referrer, &offer.mint_b))
+                .transpose()?;
+            let vault = model.ata(escrow, &offer.mint_a)?;
+
+            let taker_ata_a = model.init_ata(taker, &offer.mint_a);
+            let maker_ata_b = model.init_ata(&offer.maker, &offer.mint_b);
+
+            // 整单成交：金库全部给 taker，并关闭金库
+            let amount_out = model.balance(&vault).unwrap_or_default();
+            model.transfer(&vault, &taker_ata_a, amount_out)?;
+            model.tokens.remove(&vault);
+
+            // taker 支付 receive，推荐费从中分出
+            let fee = match referrer_ata_b {
+                Some(_) => fee(offer.receive, offer.referral_bps),
+                None => 0,
+            };
+            model.transfer(&taker_ata_b, &maker_ata_b, offer.receive - fee)?;
+            if let Some(referrer_ata_b) = referrer_ata_b
+                && fee > 0
+            {
+                model.transfer(&taker_ata_b, &referrer_ata_b, fee)?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn refund(&mut self, escrow: &Address) -> Result<(), ModelError> {
+        self.atomically(|model| {
+            let offer = model
+                .offers
+                .remove(escrow)
+                .ok_or(ModelError::MissingAccount)?;
+            let vault = model.ata(escrow, &offer.mint_a)?;
+            let maker_ata_a = model.init_ata(&offer.maker, &offer.mint_a);
+
+            let amount = model.balance(&vault).unwrap_or_default();
+            model.transfer(&vault, &maker_ata_a, amount)?;
+            model.tokens.remove(&vault);
+            Ok(())
+        })
+    }
+
+    /// 报价的托管地址与金库地址
+    pub fn escrow_address(maker: &Address, seed: u64) -> Address {
+        find_escrow_address(maker, seed).0
+    }
+
+    pub fn vault_address(escrow: &Address, mint: &Address) -> Address {
+        get_vault_address(escrow, mint)
+    }
+
+    fn atomically(
+        &mut self,
+        op: impl FnOnce(&mut Self) -> Result<(), ModelError>,
+    ) -> Result<(), ModelError> {
+        let mut next = self.clone();
+        op(&mut next)?;
+        *self = next;
+        Ok(())
+    }
+
+    /// 已存在的 ATA
+    fn ata(&self, owner: &Address, mint: &Address) -> Result<Address, ModelError> {
+        let address = get_associated_token_address(owner, mint, &TOKEN_PROGRAM_ID);
+        match self.tokens.get(&address) {
+            Some(_) => Ok(address),
+            None => Err(ModelError::MissingAccount),
+        }
+    }
+
+    /// 对应 ATA 程序的 `CreateIdempotent`：不存在时创建空账户
+    fn init_ata(&mut self, owner: &Address, mint: &Address) -> Address {
+        let address = get_associated_token_address(owner, mint, &TOKEN_PROGRAM_ID);
+        self.tokens.entry(address).or_insert(TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount: 0,
+        });
+        address
+    }
+
+    /// SPL Token 的 Transfer：先检查余额，转给自己时不改变任何状态
+    fn transfer(&mut self, from: &Address, to: &Address, amount: u64) -> Result<(), ModelError> {
+        let source = self.tokens.get(from).ok_or(ModelError::MissingAccount)?;
+        if source.amount < amount {
+            return Err(ModelError::InsufficientFunds);
+        }
+        if !self.tokens.contains_key(to) {
+            return Err(ModelError::MissingAccount);
+        }
+        if from == to {
+            return Ok(());
+        }
+
+        self.tokens.get_mut(from).unwrap().amount -= amount;
+        let destination = self.tokens.get_mut(to).unwrap();
+        destination.amount = destination
+            .amount
+            .checked_add(amount)
+            .ok_or(ModelError::InsufficientFunds)?;
+        Ok(())
+    }
+}
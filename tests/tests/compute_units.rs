@@ -0,0 +1,275 @@
+//! 统计新旧两版程序 Make / Take / Refund 的 CU 消耗，并与 `compute_units.txt` 中的基线比较
+//!
+//! - 超过基线 `CU_THRESHOLD_PCT` (默认 5) 个百分点即失败；
+//! - `UPDATE_CU_BASELINE=1` 时用本次结果覆盖基线 (未能加载的程序保留原值)；
+//! - 已加载的程序在基线中没有对应条目也失败 (先运行 `just bench-update`)。
+//!
+//! 用 `just bench` 查看报告，`just bench-update` 更新基线。
+
+use std::{collections::BTreeMap, fs};
+
+use escrow_client::{
+    Address, Instruction, ProgramFeatures, find_escrow_address, make,
+    program::{Escrow, MakeInstructionData},
+    refund, take,
+};
+use escrow_tests::{Harness, classic};
+
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/compute_units.txt");
+const DEFAULT_THRESHOLD_PCT: u64 = 5;
+
+const SEED: u64 = 7;
+const AMOUNT: u64 = 1_000;
+const RECEIVE: u64 = 5_000;
+
+const MODERN: &str = "pinocchio_escrow";
+const CLASSIC: &str = "solana_pinocchio_escrow";
+
+type Baseline = BTreeMap<(String, String), u64>;
+
+struct Offer {
+    maker: Address,
+    taker: Address,
+    mint_a: Address,
+    mint_b: Address,
+}
+
+impl Offer {
+    fn new(harness: &Harness) -> Self {
+        let maker = harness.wallet();
+        let taker = harness.wallet();
+        let mint_a = harness.mint();
+        let mint_b = harness.mint();
+        harness.token_account(&maker, &mint_a, AMOUNT);
+        harness.token_account(&taker, &mint_b, RECEIVE);
+        Self {
+            maker,
+            taker,
+            mint_a,
+            mint_b,
+        }
+    }
+}
+
+/// 执行指令并返回消耗的 CU；失败时直接报错，避免把错误路径的 CU 当作结果
+fn units(harness: &Harness, name: &str, instruction: &Instruction) -> u64 {
+    let result = harness.process(instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "{name}: {:?}",
+        result.raw_result
+    );
+    result.compute_units_consumed
+}
+
+fn measure_modern() -> Option<Vec<(&'static str, u64)>> {
+    let harness = Harness::new()?;
+    let args = MakeInstructionData {
+        seed: SEED,
+        receive: RECEIVE,
+        amount: AMOUNT,
+        referral_bps: 0,
+        rent_recipient: None,
+        proceeds_recipient: None,
+        settlement: Escrow::SETTLE_PUSH,
+    };
+    let make = |offer: &Offer| {
+        let instruction = make(
+            &offer.maker,
+            &offer.mint_a,
+            &offer.mint_b,
+            &args,
+            ProgramFeatures::default(),
+        );
+        let units = units(&harness, "make", &instruction);
+        let (address, _) = find_escrow_address(&offer.maker, SEED);
+        (units, address, harness.escrow(&address).unwrap())
+    };
+
+    let offer = Offer::new(&harness);
+    let (make_units, address, escrow) = make(&offer);
+    let take_units = units(
+        &harness,
+        "take",
+        &take(
+            &offer.taker,
+            &address,
+            &escrow,
+            None,
+            AMOUNT,
+            ProgramFeatures::default(),
+        ),
+    );
+
+    let offer = Offer::new(&harness);
+    let (_, address, escrow) = make(&offer);
+    let refund_units = units(
+        &harness,
+        "refund",
+        &refund(&address, &escrow, ProgramFeatures::default()),
+    );
+
+    Some(vec![
+        ("make", make_units),
+        ("take", take_units),
+        ("refund", refund_units),
+    ])
+}
+
+fn measure_classic() -> Option<Vec<(&'static str, u64)>> {
+    let harness = Harness::classic()?;
+    let make = |offer: &Offer| {
+        let instruction = classic::make(
+            &offer.maker,
+            &offer.mint_a,
+            &offer.mint_b,
+            SEED,
+            RECEIVE,
+            AMOUNT,
+        );
+        units(&harness, "classic make", &instruction)
+    };
+
+    let offer = Offer::new(&harness);
+    let make_units = make(&offer);
+    let take_units = units(
+        &harness,
+        "classic take",
+        &classic::take(
+            &offer.taker,
+            &offer.maker,
+            &offer.mint_a,
+            &offer.mint_b,
+            SEED,
+        ),
+    );
+
+    let offer = Offer::new(&harness);
+    make(&offer);
+    let refund_units = units(
+        &harness,
+        "classic refund",
+        &classic::refund(&offer.maker, &offer.mint_a, SEED),
+    );
+
+    Some(vec![
+        ("make", make_units),
+        ("take", take_units),
+        ("refund", refund_units),
+    ])
+}
+
+fn read_baseline() -> Baseline {
+    let Ok(text) = fs::read_to_string(BASELINE_PATH) else {
+        return Baseline::new();
+    };
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let [program, instruction, units] = line.split_whitespace().collect::<Vec<_>>()[..]
+            else {
+                panic!("malformed baseline line: {line}");
+            };
+            (
+                (program.to_string(), instruction.to_string()),
+                units.parse().expect("compute units"),
+            )
+        })
+        .collect()
+}
+
+fn write_baseline(baseline: &Baseline) {
+    let mut text = String::from(
+        "# Compute units per instruction, measured in-process with Mollusk.\n\
+         # Regenerate with `just bench-update` after `just build-all`.\n\
+         # program                instruction  units\n",
+    );
+    for ((program, instruction), units) in baseline {
+        text.push_str(&format!("{program:<24} {instruction:<12} {units}\n"));
+    }
+    fs::write(BASELINE_PATH, text).unwrap();
+}
+
+fn threshold_pct() -> u64 {
+    std::env::var("CU_THRESHOLD_PCT")
+        .ok()
+        .map_or(DEFAULT_THRESHOLD_PCT, |pct| {
+            pct.parse().expect("CU_THRESHOLD_PCT")
+        })
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn compute_units_within_baseline() {
+    let measured: Baseline = [(MODERN, measure_modern()), (CLASSIC, measure_classic())]
+        .into_iter()
+        .filter_map(|(program, samples)| Some((program, samples?)))
+        .flat_map(|(program, samples)| {
+            samples.into_iter().map(move |(instruction, units)| {
+                ((program.to_string(), instruction.to_string()), units)
+            })
+        })
+        .collect();
+    if measured.is_empty() {
+        return;
+    }
+
+    let mut baseline = read_baseline();
+    let threshold = threshold_pct();
+    let mut regressions = Vec::new();
+    let mut missing = Vec::new();
+
+    println!(
+        "{:<24} {:<8} {:>8} {:>8} {:>8}",
+        "program", "ix", "units", "baseline", "delta"
+    );
+    for ((program, instruction), &units) in &measured {
+        let Some(&base) = baseline.get(&(program.clone(), instruction.clone())) else {
+            println!(
+                "{program:<24} {instruction:<8} {units:>8} {:>8} {:>8}",
+                "-", "-"
+            );
+            missing.push(format!("{program} {instruction}"));
+            continue;
+        };
+        let delta = units as i64 - base as i64;
+        println!("{program:<24} {instruction:<8} {units:>8} {base:>8} {delta:>+8}");
+        if units * 100 > base * (100 + threshold) {
+            regressions.push(format!(
+                "{program} {instruction}: {units} CU vs baseline {base} (+{delta})"
+            ));
+        }
+    }
+
+    // 两版程序都测到时，打印新版相对旧版的 CU 占比
+    for instruction in ["make", "take", "refund"] {
+        let key = |program: &str| (program.to_string(), instruction.to_string());
+        if let (Some(modern), Some(classic)) =
+            (measured.get(&key(MODERN)), measured.get(&key(CLASSIC)))
+        {
+            println!(
+                "{instruction}: {MODERN} uses {}% of {CLASSIC}",
+                modern * 100 / classic
+            );
+        }
+    }
+
+    if std::env::var("UPDATE_CU_BASELINE").is_ok() {
+        baseline.extend(measured);
+        write_baseline(&baseline);
+        return;
+    }
+
+    assert!(
+        missing.is_empty(),
+        "no compute unit baseline for:\n{}\nrun `just bench-update` and commit tests/compute_units.txt",
+        missing.join("\n")
+    );
+    assert!(
+        regressions.is_empty(),
+        "compute units regressed by more than {threshold}%:\n{}\n\
+         run `just bench-update` if the increase is intended",
+        regressions.join("\n")
+    );
+}
@@ -0,0 +1,456 @@
+//! 新旧两版程序的差分测试：对同一组随机指令序列分别执行，比较结果
+//!
+//! 比较的内容：
+//! - 每条指令的成功 / 失败 (不比较错误码，新版使用自定义错误)；
+//! - 所有钱包 ATA 的代币余额 (账户不存在记为 `None`)；
+//! - 所有钱包的 lamports，扣除下述已知差异之后；
+//! - 每个 (maker, seed) 托管账户是否存在、两版共有的字段、其金库余额，以及托管的 lamports
+//!   是否等于各自程序的预期值。
+//!
+//! lamports 的已知差异：旧版固定以 `classic::ESCROW_LAMPORTS` 创建托管账户，新版按 Rent sysvar
+//! 计算租金；两版都在 Take / Refund 时把托管租金退给 maker (新版的租金接收者)。
+//! 每次成功的 Make / Take / Refund 都按此调整各钱包的预期差额，其余 lamports (金库与 ATA 的租金)
+//! 必须完全一致。
+//!
+//! 发现分歧时先逐条删除指令缩小序列，再连同随机种子一起报告。
+//! `DIFF_SEED` 固定起始种子，`DIFF_CASES` 调整序列数量。
+
+use escrow_client::{
+    Address, EscrowAccount, ProgramFeatures, TOKEN_PROGRAM_ID, find_escrow_address,
+    get_associated_token_address, get_vault_address, make,
+    program::{Escrow, MakeInstructionData},
+    refund, take,
+};
+use escrow_tests::{
+    Harness,
+    classic::{self, ClassicEscrow},
+};
+
+const WALLETS: usize = 3;
+const MINTS: usize = 2;
+const SEEDS: u64 = 2;
+const BALANCE: u64 = 1_000;
+const SEQUENCE_LEN: usize = 12;
+const DEFAULT_CASES: u64 = 64;
+
+/// 指令参数只引用钱包 / mint 的下标，方便报告；`swap` 表示交换两个 mint 的角色
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Make {
+        maker: usize,
+        seed: u64,
+        swap: bool,
+        amount: u64,
+        receive: u64,
+    },
+    Take {
+        taker: usize,
+        maker: usize,
+        seed: u64,
+        swap: bool,
+    },
+    Refund {
+        maker: usize,
+        seed: u64,
+        swap: bool,
+    },
+}
+
+/// SplitMix64，足以生成可复现的测试序列
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u64) as usize]
+    }
+}
+
+fn random_op(rng: &mut Rng) -> Op {
+    let wallet = |rng: &mut Rng| rng.below(WALLETS as u64) as usize;
+    match rng.below(3) {
+        0 => Op::Make {
+            maker: wallet(rng),
+            seed: rng.below(SEEDS),
+            swap: rng.below(2) == 1,
+            // 覆盖 0、余额边界与超额
+            amount: rng.pick(&[0, 1, 400, BALANCE, BALANCE + 1]),
+            receive: rng.pick(&[0, 1, 600, BALANCE, BALANCE + 1]),
+        },
+        1 => Op::Take {
+            taker: wallet(rng),
+            maker: wallet(rng),
+            seed: rng.below(SEEDS),
+            swap: rng.below(2) == 1,
+        },
+        _ => Op::Refund {
+            maker: wallet(rng),
+            seed: rng.below(SEEDS),
+            swap: rng.below(2) == 1,
+        },
+    }
+}
+
+/// 某一步的可观察状态
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    balances: Vec<Option<u64>>,
+    lamports: Vec<i128>,
+    offers: Vec<Option<Offer>>,
+}
+
+/// 两版托管账户共有的字段，以及按两个 mint 推导出的金库余额
+#[derive(Debug, PartialEq)]
+struct Offer {
+    seed: u64,
+    maker: Address,
+    mint_a: Address,
+    mint_b: Address,
+    receive: u64,
+    bump: u8,
+    vaults: Vec<Option<u64>>,
+    /// 托管 lamports 等于该程序创建托管时的预期值
+    funded: bool,
+}
+
+#[derive(Debug)]
+struct Divergence {
+    step: usize,
+    modern: (bool, Snapshot),
+    classic: (bool, Snapshot),
+}
+
+struct World {
+    wallets: [Address; WALLETS],
+    mints: [Address; MINTS],
+    modern: Harness,
+    classic: Harness,
+    /// 新版托管账户的租金
+    modern_rent: u64,
+    /// 每个钱包新版减旧版的预期 lamports 差额
+    offsets: [i128; WALLETS],
+}
+
+impl World {
+    fn new() -> Option<Self> {
+        let world = Self {
+            wallets: [(); WALLETS].map(|_| Address::new_unique()),
+            mints: [(); MINTS].map(|_| Address::new_unique()),
+            modern: Harness::new()?,
+            classic: Harness::classic()?,
+            modern_rent: 0,
+            offsets: [0; WALLETS],
+        };
+        for harness in [&world.modern, &world.classic] {
+            for mint in &world.mints {
+                harness.add_mint(mint);
+            }
+            for wallet in &world.wallets {
+                harness.add_wallet(wallet);
+                for mint in &world.mints {
+                    harness.token_account(wallet, mint, BALANCE);
+                }
+            }
+        }
+        let modern_rent = world.modern.rent_exempt(Escrow::LEN);
+        Some(Self {
+            modern_rent,
+            ..world
+        })
+    }
+
+    fn mints(&self, swap: bool) -> (Address, Address) {
+        let [a, b] = self.mints;
+        if swap { (b, a) } else { (a, b) }
+    }
+
+    /// 新版 Take / Refund 按托管内容推导账户：存在时读取链上状态 (Take 的 `max_amount_in`
+    /// 取自其中的 `receive`)；mint 与旧版一样使用指令参数中的值，以便覆盖 mint 不匹配的情况
+    fn modern_escrow(&self, maker: &Address, seed: u64, swap: bool) -> (Address, EscrowAccount) {
+        let (address, bump) = find_escrow_address(maker, seed);
+        let (mint_a, mint_b) = self.mints(swap);
+        let escrow = match self.modern.escrow(&address) {
+            Some(on_chain) => EscrowAccount {
+                mint_a,
+                mint_b,
+                ..on_chain
+            },
+            // 托管不存在时指令必然失败，其余字段取 Make 的默认值
+            None => EscrowAccount {
+                seed,
+                maker: *maker,
+                mint_a,
+                mint_b,
+                receive: 0,
+                bump,
+                referral_bps: 0,
+                rent_recipient: *maker,
+                proceeds_recipient: *maker,
+                settlement: Escrow::SETTLE_PUSH,
+                status: Escrow::STATUS_OPEN,
+            },
+        };
+        (address, escrow)
+    }
+
+    /// 在两个运行时上执行同一条指令，返回各自是否成功
+    fn apply(&self, op: Op) -> (bool, bool) {
+        let (modern, classic) = match op {
+            Op::Make {
+                maker,
+                seed,
+                swap,
+                amount,
+                receive,
+            } => {
+                let maker = &self.wallets[maker];
+                let (mint_a, mint_b) = self.mints(swap);
+                let args = MakeInstructionData {
+                    seed,
+                    receive,
+                    amount,
+                    referral_bps: 0,
+                    rent_recipient: None,
+                    proceeds_recipient: None,
+                    settlement: Escrow::SETTLE_PUSH,
+                };
+                (
+                    make(maker, &mint_a, &mint_b, &args, ProgramFeatures::default()),
+                    classic::make(maker, &mint_a, &mint_b, seed, receive, amount),
+                )
+            }
+            Op::Take {
+                taker,
+                maker,
+                seed,
+                swap,
+            } => {
+                let maker = &self.wallets[maker];
+                let (mint_a, mint_b) = self.mints(swap);
+                let (address, escrow) = self.modern_escrow(maker, seed, swap);
+                let vault = get_vault_address(&address, &escrow.mint_a);
+                (
+                    take(
+                        &self.wallets[taker],
+                        &address,
+                        &escrow,
+                        None,
+                        self.modern.token_amount(&vault),
+                        ProgramFeatures::default(),
+                    ),
+                    classic::take(&self.wallets[taker], maker, &mint_a, &mint_b, seed),
+                )
+            }
+            Op::Refund { maker, seed, swap } => {
+                let maker = &self.wallets[maker];
+                let (mint_a, _) = self.mints(swap);
+                let (address, escrow) = self.modern_escrow(maker, seed, swap);
+                (
+                    refund(&address, &escrow, ProgramFeatures::default()),
+                    classic::refund(maker, &mint_a, seed),
+                )
+            }
+        };
+
+        (
+            self.modern.process(&modern).program_result.is_ok(),
+            self.classic.process(&classic).program_result.is_ok(),
+        )
+    }
+
+    /// 两版都成功执行 `op` 后，按托管租金的去向更新预期差额
+    fn settle_rent(&mut self, op: Op) {
+        let (modern, classic) = (self.modern_rent as i128, classic::ESCROW_LAMPORTS as i128);
+        match op {
+            // maker 各自为托管账户付租金
+            Op::Make { maker, .. } => self.offsets[maker] += classic - modern,
+            // Take / Refund 都把托管租金退给 maker (新版的默认租金接收者)
+            Op::Take { maker, .. } | Op::Refund { maker, .. } => {
+                self.offsets[maker] += modern - classic
+            }
+        }
+    }
+
+    fn snapshot(
+        &self,
+        harness: &Harness,
+        decode: fn(&[u8]) -> Option<ClassicEscrow>,
+        escrow_lamports: u64,
+        offsets: &[i128; WALLETS],
+    ) -> Snapshot {
+        let balance = |address: &Address| {
+            harness
+                .account(address)
+                .map(|_| harness.token_amount(address))
+        };
+
+        let balances = self
+            .wallets
+            .iter()
+            .flat_map(|wallet| {
+                self.mints
+                    .iter()
+                    .map(move |mint| get_associated_token_address(wallet, mint, &TOKEN_PROGRAM_ID))
+            })
+            .map(|ata| balance(&ata))
+            .collect();
+
+        let lamports = self
+            .wallets
+            .iter()
+            .zip(offsets)
+            .map(|(wallet, offset)| harness.lamports(wallet) as i128 + offset)
+            .collect();
+
+        let offers = self
+            .wallets
+            .iter()
+            .flat_map(|maker| (0..SEEDS).map(move |seed| find_escrow_address(maker, seed).0))
+            .map(|address| {
+                let account = harness.account(&address)?;
+                let escrow = decode(&account.data)?;
+                Some(Offer {
+                    seed: escrow.seed,
+                    maker: escrow.maker,
+                    mint_a: escrow.mint_a,
+                    mint_b: escrow.mint_b,
+                    receive: escrow.receive,
+                    bump: escrow.bump,
+                    vaults: self
+                        .mints
+                        .iter()
+                        .map(|mint| balance(&get_vault_address(&address, mint)))
+                        .collect(),
+                    funded: account.lamports == escrow_lamports,
+                })
+            })
+            .collect();
+
+        Snapshot {
+            balances,
+            lamports,
+            offers,
+        }
+    }
+}
+
+/// 新版托管账户中与旧版共有的字段
+fn decode_modern(data: &[u8]) -> Option<ClassicEscrow> {
+    let escrow = EscrowAccount::from_bytes(data).ok()?;
+    Some(ClassicEscrow {
+        seed: escrow.seed,
+        maker: escrow.maker,
+        mint_a: escrow.mint_a,
+        mint_b: escrow.mint_b,
+        receive: escrow.receive,
+        bump: escrow.bump,
+    })
+}
+
+/// 在全新的运行时上执行整个序列，返回成功的 Take 数量或第一处分歧
+fn run(ops: &[Op]) -> Option<Result<usize, Divergence>> {
+    let mut world = World::new()?;
+    let mut takes = 0;
+    for (step, &op) in ops.iter().enumerate() {
+        let (modern_ok, classic_ok) = world.apply(op);
+        if modern_ok && classic_ok {
+            world.settle_rent(op);
+            takes += matches!(op, Op::Take { .. }) as usize;
+        }
+        // 差额记在旧版一侧，新版快照按原值比较
+        let modern = world.snapshot(
+            &world.modern,
+            decode_modern,
+            world.modern_rent,
+            &[0; WALLETS],
+        );
+        let classic = world.snapshot(
+            &world.classic,
+            ClassicEscrow::from_bytes,
+            classic::ESCROW_LAMPORTS,
+            &world.offsets,
+        );
+        if modern_ok != classic_ok || modern != classic {
+            return Some(Err(Divergence {
+                step,
+                modern: (modern_ok, modern),
+                classic: (classic_ok, classic),
+            }));
+        }
+    }
+    Some(Ok(takes))
+}
+
+/// 逐条删除指令，只要仍有分歧就保留删除，直到无法再缩小
+fn minimize(mut ops: Vec<Op>) -> (Vec<Op>, Divergence) {
+    let mut i = 0;
+    while i < ops.len() {
+        let mut candidate = ops.clone();
+        candidate.remove(i);
+        if matches!(run(&candidate), Some(Err(_))) {
+            ops = candidate;
+        } else {
+            i += 1;
+        }
+    }
+    let Some(Err(divergence)) = run(&ops) else {
+        unreachable!("minimized sequence must still diverge");
+    };
+    ops.truncate(divergence.step + 1);
+    (ops, divergence)
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.parse().unwrap_or_else(|_| panic!("{name}")))
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn classic_and_modern_agree() {
+    let base_seed = env_u64("DIFF_SEED").unwrap_or(0x5eed);
+    let cases = env_u64("DIFF_CASES").unwrap_or(DEFAULT_CASES);
+    let mut takes = 0;
+
+    for case in 0..cases {
+        let seed = base_seed.wrapping_add(case);
+        let mut rng = Rng(seed);
+        let ops: Vec<Op> = (0..SEQUENCE_LEN).map(|_| random_op(&mut rng)).collect();
+
+        match run(&ops) {
+            // 任一程序未编译时跳过
+            None => return,
+            Some(Ok(count)) => takes += count,
+            Some(Err(_)) => {
+                let (ops, divergence) = minimize(ops);
+                panic!(
+                    "classic and modern programs diverge (DIFF_SEED={seed} DIFF_CASES=1)\n\
+                     minimized sequence:\n{ops:#?}\n\
+                     at step {}:\nmodern  (ok = {}): {:#?}\nclassic (ok = {}): {:#?}",
+                    divergence.step,
+                    divergence.modern.0,
+                    divergence.modern.1,
+                    divergence.classic.0,
+                    divergence.classic.1,
+                );
+            }
+        }
+    }
+
+    // 两版都一直失败的 Take 也算“一致”，至少要有一次真正成交
+    if cases >= DEFAULT_CASES {
+        assert!(takes > 0, "no Take succeeded in {cases} sequences");
+    }
+}
@@ -0,0 +1,716 @@
+//! 在进程内 SVM 中跑完整的报价生命周期，并核对各方余额
+
+use escrow_client::{
+    AccountFlagFailure, Address, EscrowAccount, ProgramFeatures, TOKEN_PROGRAM_ID, claim,
+    find_escrow_address, get_associated_token_address, get_proceeds_vault_address,
+    get_vault_address, make, none_account,
+    program::{Config, Escrow, EscrowError, LegacyEscrow, MakeInstructionData, Take},
+    refund, take,
+};
+use escrow_tests::{Harness, solana_account::Account};
+use solana_instruction::error::InstructionError;
+
+const SEED: u64 = 42;
+const AMOUNT: u64 = 1_000;
+const RECEIVE: u64 = 5_000;
+
+struct Offer {
+    maker: Address,
+    taker: Address,
+    mint_a: Address,
+    mint_b: Address,
+    escrow: Address,
+    vault: Address,
+}
+
+fn make_args(referral_bps: u16, settlement: u8) -> MakeInstructionData {
+    MakeInstructionData {
+        seed: SEED,
+        receive: RECEIVE,
+        amount: AMOUNT,
+        referral_bps,
+        rent_recipient: None,
+        proceeds_recipient: None,
+        settlement,
+    }
+}
+
+/// 准备钱包与代币余额 (maker 持有 A，taker 持有 B)
+fn setup(harness: &Harness) -> Offer {
+    let maker = harness.wallet();
+    let taker = harness.wallet();
+    let mint_a = harness.mint();
+    let mint_b = harness.mint();
+    harness.token_account(&maker, &mint_a, AMOUNT);
+    harness.token_account(&taker, &mint_b, RECEIVE);
+
+    let (escrow, _) = find_escrow_address(&maker, SEED);
+    Offer {
+        maker,
+        taker,
+        mint_a,
+        mint_b,
+        escrow,
+        vault: get_vault_address(&escrow, &mint_a),
+    }
+}
+
+fn make_offer(harness: &Harness, offer: &Offer, args: &MakeInstructionData) -> EscrowAccount {
+    let instruction = make(
+        &offer.maker,
+        &offer.mint_a,
+        &offer.mint_b,
+        args,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "make: {:?}",
+        result.raw_result
+    );
+
+    harness.escrow(&offer.escrow).expect("escrow account")
+}
+
+fn ata(owner: &Address, mint: &Address) -> Address {
+    get_associated_token_address(owner, mint, &TOKEN_PROGRAM_ID)
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn make_then_take() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let maker_lamports = harness.lamports(&offer.maker);
+
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_PUSH));
+    assert_eq!(escrow.maker, offer.maker);
+    assert_eq!(escrow.receive, RECEIVE);
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+    assert_eq!(harness.token_amount(&ata(&offer.maker, &offer.mint_a)), 0);
+
+    // Make 的租金由 maker 支付
+    let rent = harness.lamports(&offer.escrow) + harness.lamports(&offer.vault);
+    assert_eq!(harness.lamports(&offer.maker), maker_lamports - rent);
+
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(
+        harness.token_amount(&ata(&offer.taker, &offer.mint_a)),
+        AMOUNT
+    );
+    assert_eq!(harness.token_amount(&ata(&offer.taker, &offer.mint_b)), 0);
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_b)),
+        RECEIVE
+    );
+
+    // 托管与金库关闭，租金全部退回 maker
+    assert!(harness.account(&offer.escrow).is_none());
+    assert!(harness.account(&offer.vault).is_none());
+    assert_eq!(harness.lamports(&offer.maker), maker_lamports);
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn make_then_refund() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let maker_lamports = harness.lamports(&offer.maker);
+
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_PUSH));
+
+    let instruction = refund(&offer.escrow, &escrow, ProgramFeatures::default());
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "refund: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_a)),
+        AMOUNT
+    );
+    assert!(harness.account(&offer.escrow).is_none());
+    assert!(harness.account(&offer.vault).is_none());
+    assert_eq!(harness.lamports(&offer.maker), maker_lamports);
+
+    // taker 的 Token B 未被动用
+    assert_eq!(
+        harness.token_amount(&ata(&offer.taker, &offer.mint_b)),
+        RECEIVE
+    );
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn take_pays_referral_fee() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let referrer = harness.wallet();
+    let referrer_ata_b = harness.token_account(&referrer, &offer.mint_b, 0);
+
+    // 2% 推荐费，从 receive 中分出
+    let escrow = make_offer(&harness, &offer, &make_args(200, Escrow::SETTLE_PUSH));
+    let fee = RECEIVE * 200 / 10_000;
+
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        Some(&referrer),
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(harness.token_amount(&referrer_ata_b), fee);
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_b)),
+        RECEIVE - fee
+    );
+    assert_eq!(harness.token_amount(&ata(&offer.taker, &offer.mint_b)), 0);
+    assert_eq!(
+        harness.token_amount(&ata(&offer.taker, &offer.mint_a)),
+        AMOUNT
+    );
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn taker_cannot_be_the_referrer() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = make_offer(&harness, &offer, &make_args(200, Escrow::SETTLE_PUSH));
+
+    // taker 与 maker 自荐为推荐人都会被拒绝，金库不动
+    for referrer in [offer.taker, offer.maker] {
+        harness.token_account(&referrer, &offer.mint_b, 0);
+        let instruction = take(
+            &offer.taker,
+            &offer.escrow,
+            &escrow,
+            Some(&referrer),
+            AMOUNT,
+            ProgramFeatures::default(),
+        );
+        let result = harness.process(&instruction);
+        assert_eq!(
+            result.raw_result,
+            Err(InstructionError::Custom(
+                EscrowError::InvalidReferrer as u32
+            ))
+        );
+    }
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn proceeds_recipient_cannot_be_the_referrer() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let recipient = harness.wallet();
+    let escrow = make_offer(
+        &harness,
+        &offer,
+        &MakeInstructionData {
+            proceeds_recipient: Some(recipient),
+            ..make_args(200, Escrow::SETTLE_PUSH)
+        },
+    );
+
+    // 推荐费应付给第三方，收款人不能再以推荐人身份收回它
+    harness.token_account(&recipient, &offer.mint_b, 0);
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        Some(&recipient),
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert_eq!(
+        result.raw_result,
+        Err(InstructionError::Custom(
+            EscrowError::InvalidReferrer as u32
+        ))
+    );
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn take_rejects_mints_other_than_the_escrows() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_PUSH));
+
+    // taker 自己发行的 mint 冒充 mint_b (用它付款)，或冒充 mint_a (配上自己的"金库")
+    let junk = harness.mint();
+    harness.token_account(&offer.taker, &junk, RECEIVE);
+    harness.token_account(&offer.escrow, &junk, AMOUNT);
+    for forged in [
+        EscrowAccount {
+            mint_b: junk,
+            ..escrow
+        },
+        EscrowAccount {
+            mint_a: junk,
+            ..escrow
+        },
+    ] {
+        let instruction = take(
+            &offer.taker,
+            &offer.escrow,
+            &forged,
+            None,
+            AMOUNT,
+            ProgramFeatures::default(),
+        );
+        let result = harness.process(&instruction);
+        assert_eq!(result.raw_result, Err(InstructionError::InvalidArgument));
+    }
+
+    // 金库不动，taker 的代币也未被转出
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+    assert_eq!(harness.token_amount(&ata(&offer.taker, &junk)), RECEIVE);
+    assert!(harness.account(&ata(&offer.taker, &offer.mint_a)).is_none());
+}
+
+/// 预置引入推荐费之前创建的 113 字节托管，以及其中的 Token A
+fn legacy_offer(harness: &Harness, offer: &Offer) -> EscrowAccount {
+    let (_, bump) = find_escrow_address(&offer.maker, SEED);
+    let data = [
+        &SEED.to_le_bytes()[..],
+        offer.maker.as_ref(),
+        offer.mint_a.as_ref(),
+        offer.mint_b.as_ref(),
+        &RECEIVE.to_le_bytes(),
+        &[bump],
+    ]
+    .concat();
+    assert_eq!(data.len(), LegacyEscrow::LEN);
+    harness.set_account(
+        offer.escrow,
+        Account {
+            lamports: harness.rent_exempt(LegacyEscrow::LEN),
+            data,
+            owner: escrow_client::PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    harness.token_account(&offer.escrow, &offer.mint_a, AMOUNT);
+
+    harness.escrow(&offer.escrow).expect("legacy escrow")
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn legacy_escrow_can_be_refunded() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = legacy_offer(&harness, &offer);
+    harness.token_account(&offer.maker, &offer.mint_a, 0);
+    let rent = harness.lamports(&offer.escrow) + harness.lamports(&offer.vault);
+    let maker_lamports = harness.lamports(&offer.maker);
+
+    let instruction = refund(&offer.escrow, &escrow, ProgramFeatures::default());
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "refund: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_a)),
+        AMOUNT
+    );
+    assert!(harness.account(&offer.escrow).is_none());
+    assert!(harness.account(&offer.vault).is_none());
+
+    // 旧版托管没有租金接收者字段，租金退回 maker
+    assert_eq!(harness.lamports(&offer.maker), maker_lamports + rent);
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn legacy_escrow_can_be_taken() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = legacy_offer(&harness, &offer);
+    let rent = harness.lamports(&offer.escrow) + harness.lamports(&offer.vault);
+    let maker_lamports = harness.lamports(&offer.maker);
+
+    // 旧版托管没有推荐费：带上推荐人也不分走任何 Token B
+    let referrer = harness.wallet();
+    let referrer_ata_b = harness.token_account(&referrer, &offer.mint_b, 0);
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        Some(&referrer),
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(
+        harness.token_amount(&ata(&offer.taker, &offer.mint_a)),
+        AMOUNT
+    );
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_b)),
+        RECEIVE
+    );
+    assert_eq!(harness.token_amount(&referrer_ata_b), 0);
+    assert!(harness.account(&offer.escrow).is_none());
+    assert!(harness.account(&offer.vault).is_none());
+    assert_eq!(harness.lamports(&offer.maker), maker_lamports + rent);
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn claim_mode_make_take_claim() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let maker_lamports = harness.lamports(&offer.maker);
+    let proceeds_vault = get_proceeds_vault_address(&offer.escrow, &offer.mint_b);
+
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_CLAIM));
+    assert!(escrow.is_claim_mode());
+    assert_eq!(harness.token_amount(&proceeds_vault), 0);
+
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+
+    // Token B 停在收益金库，托管记录保留并标记为已成交
+    assert_eq!(harness.token_amount(&proceeds_vault), RECEIVE);
+    assert_eq!(
+        harness.token_amount(&ata(&offer.taker, &offer.mint_a)),
+        AMOUNT
+    );
+    let filled = harness
+        .escrow(&offer.escrow)
+        .expect("escrow kept until claim");
+    assert!(filled.is_filled());
+
+    let instruction = claim(&offer.escrow, &filled, ProgramFeatures::default());
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "claim: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_b)),
+        RECEIVE
+    );
+    assert!(harness.account(&offer.escrow).is_none());
+    assert!(harness.account(&proceeds_vault).is_none());
+
+    // maker 额外支付了自己 mint_b ATA 的租金
+    let maker_ata_b_rent = harness.lamports(&ata(&offer.maker, &offer.mint_b));
+    assert_eq!(
+        harness.lamports(&offer.maker),
+        maker_lamports - maker_ata_b_rent
+    );
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn refund_drains_donated_proceeds() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let proceeds_vault = get_proceeds_vault_address(&offer.escrow, &offer.mint_b);
+
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_CLAIM));
+
+    // 任何人都能向收益金库转入 mint_b，Refund 不能因此无法关闭它
+    assert_eq!(
+        harness.token_account(&offer.escrow, &offer.mint_b, 7),
+        proceeds_vault
+    );
+
+    let instruction = refund(&offer.escrow, &escrow, ProgramFeatures::default());
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "refund: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(harness.token_amount(&ata(&offer.maker, &offer.mint_b)), 7);
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_a)),
+        AMOUNT
+    );
+    assert!(harness.account(&proceeds_vault).is_none());
+    assert!(harness.account(&offer.escrow).is_none());
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn paused_make_is_rejected() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    harness.set_config(&Address::new_unique(), Config::PAUSE_MAKE);
+
+    let instruction = make(
+        &offer.maker,
+        &offer.mint_a,
+        &offer.mint_b,
+        &make_args(0, Escrow::SETTLE_PUSH),
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert_eq!(
+        result.raw_result,
+        Err(InstructionError::Custom(
+            EscrowError::InstructionPaused as u32
+        ))
+    );
+    assert!(harness.account(&offer.escrow).is_none());
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_a)),
+        AMOUNT
+    );
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn missing_config_is_not_paused() {
+    // 升级后尚未 InitConfig 的部署：Config PDA 不存在
+    let Some(harness) = Harness::with_program(&escrow_client::PROGRAM_ID, "pinocchio_escrow")
+    else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_PUSH));
+
+    // 换成其他未初始化的账户则不能绕过暂停检查
+    let mut instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    instruction.accounts[Take::CONFIG_INDEX].pubkey = harness.wallet();
+    let result = harness.process(&instruction);
+    assert_eq!(result.raw_result, Err(InstructionError::InvalidSeeds));
+
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn readonly_vault_is_rejected_before_any_cpi() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_PUSH));
+
+    let mut instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let vault = instruction
+        .accounts
+        .iter()
+        .position(|meta| meta.pubkey == offer.vault)
+        .unwrap();
+    instruction.accounts[vault].is_writable = false;
+
+    let result = harness.process(&instruction);
+    let Err(InstructionError::Custom(code)) = result.raw_result else {
+        panic!("take: {:?}", result.raw_result);
+    };
+    let failure = AccountFlagFailure::from_code(code, &Take::ACCOUNTS).unwrap();
+    assert_eq!(failure.account, "vault");
+
+    // taker_ata_a 尚未创建，说明在 ATA 的 CPI 之前就已拒绝
+    assert!(harness.account(&ata(&offer.taker, &offer.mint_a)).is_none());
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn none_sentinel_skips_the_referrer() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = make_offer(&harness, &offer, &make_args(1_000, Escrow::SETTLE_PUSH));
+
+    // 推荐人位置传入程序 ID：视为未提供，不收推荐费
+    let mut instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    instruction.accounts.push(none_account());
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+
+    assert_eq!(
+        harness.token_amount(&ata(&offer.maker, &offer.mint_b)),
+        RECEIVE
+    );
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+fn take_fails_when_the_offer_was_repriced() {
+    let Some(harness) = Harness::new() else {
+        return;
+    };
+    let offer = setup(&harness);
+    let escrow = make_offer(&harness, &offer, &make_args(0, Escrow::SETTLE_PUSH));
+
+    // taker 按较低的售价签名：报价条款已不同，成交失败且金库不动
+    let stale = EscrowAccount {
+        receive: RECEIVE - 1,
+        ..escrow
+    };
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &stale,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert_eq!(
+        result.raw_result,
+        Err(InstructionError::Custom(
+            EscrowError::SlippageExceeded as u32
+        ))
+    );
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+
+    // 金库余额少于 taker 读到的数量 (报价被重建为更少的 Token A) 同样失败
+    let instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT + 1,
+        ProgramFeatures::default(),
+    );
+    let result = harness.process(&instruction);
+    assert_eq!(
+        result.raw_result,
+        Err(InstructionError::Custom(
+            EscrowError::SlippageExceeded as u32
+        ))
+    );
+    assert_eq!(harness.token_amount(&offer.vault), AMOUNT);
+
+    // 只发判别符的旧客户端不受限制
+    let mut instruction = take(
+        &offer.taker,
+        &offer.escrow,
+        &escrow,
+        None,
+        AMOUNT,
+        ProgramFeatures::default(),
+    );
+    instruction.data.truncate(1);
+    let result = harness.process(&instruction);
+    assert!(
+        result.program_result.is_ok(),
+        "take: {:?}",
+        result.raw_result
+    );
+}
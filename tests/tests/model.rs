@@ -0,0 +1,369 @@
+//! 参考模型与真实程序的属性测试：proptest 生成随机操作序列，逐步比较两者
+//!
+//! 每一步都检查：
+//! - 模型与程序的成功 / 失败一致；
+//! - 所有钱包 ATA 与金库的余额、所有报价的条款一致；
+//! - 代币守恒：每个 mint 在钱包与金库中的余额总和不变 (模型与程序分别检查)。
+//!
+//! `model_conserves_tokens` 只运行模型并检查其守恒性；`program_matches_model` 需要
+//! `target/deploy/pinocchio_escrow.so`，默认忽略，用 `just test-sbf` 运行。
+
+use escrow_client::{
+    Address, EscrowAccount, ProgramFeatures, TOKEN_PROGRAM_ID, get_associated_token_address, make,
+    program::{Escrow, MAX_BPS, MakeInstructionData},
+    refund, take,
+};
+use escrow_tests::{
+    Harness,
+    model::{Model, Offer},
+};
+use proptest::prelude::*;
+
+const WALLETS: usize = 3;
+const MINTS: usize = 2;
+const SEEDS: u64 = 2;
+const BALANCE: u64 = 1_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Make {
+        maker: usize,
+        seed: u64,
+        swap: bool,
+        amount: u64,
+        receive: u64,
+        referral_bps: u16,
+    },
+    Take {
+        taker: usize,
+        maker: usize,
+        seed: u64,
+        referrer: Option<usize>,
+    },
+    Refund {
+        maker: usize,
+        seed: u64,
+    },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let wallet = 0..WALLETS;
+    let seed = 0..SEEDS;
+    prop_oneof![
+        (
+            wallet.clone(),
+            seed.clone(),
+            any::<bool>(),
+            // 覆盖 0、余额边界与超额
+            prop_oneof![Just(0), 1..=BALANCE, Just(BALANCE + 1)],
+            prop_oneof![Just(0), 1..=BALANCE, Just(BALANCE + 1)],
+            prop_oneof![Just(0), 1..=MAX_BPS, Just(MAX_BPS + 1)],
+        )
+            .prop_map(
+                |(maker, seed, swap, amount, receive, referral_bps)| Op::Make {
+                    maker,
+                    seed,
+                    swap,
+                    amount,
+                    receive,
+                    referral_bps,
+                }
+            ),
+        (
+            wallet.clone(),
+            wallet.clone(),
+            seed.clone(),
+            proptest::option::of(wallet.clone()),
+        )
+            .prop_map(|(taker, maker, seed, referrer)| Op::Take {
+                taker,
+                maker,
+                seed,
+                referrer,
+            }),
+        (wallet, seed).prop_map(|(maker, seed)| Op::Refund { maker, seed }),
+    ]
+}
+
+struct World {
+    wallets: [Address; WALLETS],
+    mints: [Address; MINTS],
+    model: Model,
+    program: Option<Harness>,
+}
+
+impl World {
+    /// `with_program` 为 false 时只构造模型
+    fn new(with_program: bool) -> Self {
+        let wallets = [(); WALLETS].map(|_| Address::new_unique());
+        let mints = [(); MINTS].map(|_| Address::new_unique());
+        let mut model = Model::new();
+        let program = if with_program { Harness::new() } else { None };
+
+        for wallet in &wallets {
+            for mint in &mints {
+                model.token_account(wallet, mint, BALANCE);
+            }
+        }
+        if let Some(harness) = &program {
+            for mint in &mints {
+                harness.add_mint(mint);
+            }
+            for wallet in &wallets {
+                harness.add_wallet(wallet);
+                for mint in &mints {
+                    harness.token_account(wallet, mint, BALANCE);
+                }
+            }
+        }
+
+        Self {
+            wallets,
+            mints,
+            model,
+            program,
+        }
+    }
+
+    /// 程序的 Take / Refund 需要托管内容来推导账户：报价存在时取模型中的条款，
+    /// 否则按 maker 与种子补全 (此时两边都应失败)
+    fn escrow_account(&self, maker: &Address, seed: u64) -> (Address, EscrowAccount) {
+        let (address, bump) = escrow_client::find_escrow_address(maker, seed);
+        let (mint_a, mint_b, receive, referral_bps) = match self.model.offers.get(&address) {
+            Some(offer) => (
+                offer.mint_a,
+                offer.mint_b,
+                offer.receive,
+                offer.referral_bps,
+            ),
+            None => (self.mints[0], self.mints[1], 0, 0),
+        };
+        let escrow = EscrowAccount {
+            seed,
+            maker: *maker,
+            mint_a,
+            mint_b,
+            receive,
+            bump,
+            referral_bps,
+            rent_recipient: *maker,
+            proceeds_recipient: *maker,
+            settlement: Escrow::SETTLE_PUSH,
+            status: Escrow::STATUS_OPEN,
+        };
+        (address, escrow)
+    }
+
+    /// 在模型与程序上执行同一条操作，返回模型与程序 (若已加载) 是否成功
+    fn apply(&mut self, op: Op) -> (bool, Option<bool>) {
+        let features = ProgramFeatures::default();
+        let (model, instruction) = match op {
+            Op::Make {
+                maker,
+                seed,
+                swap,
+                amount,
+                receive,
+                referral_bps,
+            } => {
+                let maker = self.wallets[maker];
+                let [mut mint_a, mut mint_b] = self.mints;
+                if swap {
+                    (mint_a, mint_b) = (mint_b, mint_a);
+                }
+                let args = MakeInstructionData {
+                    seed,
+                    receive,
+                    amount,
+                    referral_bps,
+                    rent_recipient: None,
+                    proceeds_recipient: None,
+                    settlement: Escrow::SETTLE_PUSH,
+                };
+                let instruction = make(&maker, &mint_a, &mint_b, &args, features);
+                (
+                    self.model.make(&maker, &mint_a, &mint_b, &args),
+                    instruction,
+                )
+            }
+            Op::Take {
+                taker,
+                maker,
+                seed,
+                referrer,
+            } => {
+                let taker = self.wallets[taker];
+                let referrer = referrer.map(|referrer| self.wallets[referrer]);
+                let (address, escrow) = self.escrow_account(&self.wallets[maker], seed);
+                // 至少收到读取时的金库余额，与真实客户端的用法一致
+                let vault = Model::vault_address(&address, &escrow.mint_a);
+                let min_amount_out = self.model.balance(&vault).unwrap_or_default();
+                let instruction = take(
+                    &taker,
+                    &address,
+                    &escrow,
+                    referrer.as_ref(),
+                    min_amount_out,
+                    features,
+                );
+                (
+                    self.model.take(&taker, &address, referrer.as_ref()),
+                    instruction,
+                )
+            }
+            Op::Refund { maker, seed } => {
+                let (address, escrow) = self.escrow_account(&self.wallets[maker], seed);
+                let instruction = refund(&address, &escrow, features);
+                (self.model.refund(&address), instruction)
+            }
+        };
+
+        let program = self
+            .program
+            .as_ref()
+            .map(|harness| harness.process(&instruction).program_result.is_ok());
+        (model.is_ok(), program)
+    }
+
+    /// 所有可能持有代币的账户：钱包 ATA 与每个 (maker, seed, mint) 的金库
+    fn token_accounts(&self) -> Vec<(Address, Address)> {
+        let wallets = self.wallets.iter().flat_map(|wallet| {
+            self.mints.iter().map(|mint| {
+                (
+                    *mint,
+                    get_associated_token_address(wallet, mint, &TOKEN_PROGRAM_ID),
+                )
+            })
+        });
+        let vaults = self.escrows().into_iter().flat_map(|escrow| {
+            self.mints
+                .iter()
+                .map(move |mint| (*mint, Model::vault_address(&escrow, mint)))
+        });
+        wallets.chain(vaults).collect()
+    }
+
+    fn escrows(&self) -> Vec<Address> {
+        self.wallets
+            .iter()
+            .flat_map(|maker| (0..SEEDS).map(move |seed| Model::escrow_address(maker, seed)))
+            .collect()
+    }
+
+    fn model_balances(&self) -> Vec<Option<u64>> {
+        self.token_accounts()
+            .iter()
+            .map(|(_, address)| self.model.balance(address))
+            .collect()
+    }
+
+    fn program_balances(&self, harness: &Harness) -> Vec<Option<u64>> {
+        self.token_accounts()
+            .iter()
+            .map(|(_, address)| {
+                harness
+                    .account(address)
+                    .map(|_| harness.token_amount(address))
+            })
+            .collect()
+    }
+
+    fn model_offers(&self) -> Vec<Option<Offer>> {
+        self.escrows()
+            .iter()
+            .map(|escrow| self.model.offers.get(escrow).cloned())
+            .collect()
+    }
+
+    fn program_offers(&self, harness: &Harness) -> Vec<Option<Offer>> {
+        self.escrows()
+            .iter()
+            .map(|escrow| {
+                let escrow = harness.escrow(escrow)?;
+                Some(Offer {
+                    seed: escrow.seed,
+                    maker: escrow.maker,
+                    mint_a: escrow.mint_a,
+                    mint_b: escrow.mint_b,
+                    receive: escrow.receive,
+                    referral_bps: escrow.referral_bps,
+                })
+            })
+            .collect()
+    }
+
+    /// 按 mint 汇总余额
+    fn supplies(&self, balances: &[Option<u64>]) -> Vec<u128> {
+        let accounts = self.token_accounts();
+        self.mints
+            .iter()
+            .map(|mint| {
+                accounts
+                    .iter()
+                    .zip(balances)
+                    .filter(|((account_mint, _), _)| account_mint == mint)
+                    .map(|(_, balance)| balance.unwrap_or_default() as u128)
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// 逐步执行 `ops`，检查模型守恒；程序已加载时再与模型逐项比较
+fn check(mut world: World, ops: Vec<Op>) -> Result<(), TestCaseError> {
+    let supply = vec![(WALLETS as u128) * BALANCE as u128; MINTS];
+
+    for (step, op) in ops.into_iter().enumerate() {
+        let (model_ok, program_ok) = world.apply(op);
+
+        let model_balances = world.model_balances();
+        prop_assert_eq!(
+            world.supplies(&model_balances),
+            supply.clone(),
+            "model, step {}",
+            step
+        );
+        for mint in &world.mints {
+            prop_assert_eq!(world.model.supply(mint), supply[0], "model, step {}", step);
+        }
+
+        let Some(harness) = &world.program else {
+            continue;
+        };
+        prop_assert_eq!(Some(model_ok), program_ok, "outcome, step {}", step);
+
+        let program_balances = world.program_balances(harness);
+        prop_assert_eq!(
+            world.supplies(&program_balances),
+            supply.clone(),
+            "program, step {}",
+            step
+        );
+        prop_assert_eq!(
+            &program_balances,
+            &model_balances,
+            "balances, step {}",
+            step
+        );
+        prop_assert_eq!(
+            world.program_offers(harness),
+            world.model_offers(),
+            "offers, step {}",
+            step
+        );
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn model_conserves_tokens(ops in proptest::collection::vec(op(), 1..16)) {
+        check(World::new(false), ops)?;
+    }
+
+    #[test]
+    #[ignore = "needs the SBF build in target/deploy; run `just test-sbf`"]
+    fn program_matches_model(ops in proptest::collection::vec(op(), 1..16)) {
+        check(World::new(true), ops)?;
+    }
+}